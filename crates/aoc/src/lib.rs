@@ -0,0 +1,177 @@
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use thiserror::Error;
+
+/// The answer to one part of a puzzle.
+///
+/// The days compute their answers in whatever integer type fits the puzzle best,
+/// this type unifies them so that all days can be treated the same way.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Answer {
+    Signed(i64),
+    Unsigned(u64),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Signed(value) => write!(f, "{}", value),
+            Answer::Unsigned(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(value: i32) -> Self {
+        Answer::Signed(value.into())
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Answer::Signed(value)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(value: u32) -> Self {
+        Answer::Unsigned(value.into())
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        Answer::Unsigned(value)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::Unsigned(value as u64)
+    }
+}
+
+/// A solution to both parts of a day's puzzle.
+///
+/// The input is parsed once and then shared by both parts,
+/// so that runners and benchmarks can time the phases separately.
+pub trait Solution {
+    /// The puzzle input as it is understood by both parts
+    type Input;
+
+    /// Describes what is wrong with a malformed puzzle input
+    type Error: std::error::Error + 'static;
+
+    /// The puzzle input that is shipped with the day
+    const INPUT: &'static str;
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error>;
+
+    /// Fails for inputs that parse but have no answer, e.g. a game that nobody wins
+    fn part1(input: &Self::Input) -> Result<Answer, Self::Error>;
+
+    fn part2(input: &Self::Input) -> Result<Answer, Self::Error>;
+
+    /// Parses a puzzle input that is read to its end first, e.g. from stdin
+    fn parse_reader(mut reader: impl Read) -> Result<Self::Input, LoadError<Self::Error>> {
+        let mut input = String::new();
+        reader.read_to_string(&mut input)?;
+        Self::parse(&input).map_err(LoadError::Parse)
+    }
+
+    /// Parses the puzzle input stored in a file
+    fn parse_file(path: impl AsRef<Path>) -> Result<Self::Input, LoadError<Self::Error>> {
+        Self::parse(&fs::read_to_string(path)?).map_err(LoadError::Parse)
+    }
+}
+
+/// Loading a puzzle input can fail while reading it or because it is malformed
+#[derive(Debug, Error)]
+pub enum LoadError<E: std::error::Error + 'static> {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Parse(E),
+}
+
+/// Iterates over the lines of an input that have content, together with their 1-based line number
+pub fn numbered_lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty())
+}
+
+/// The 1-based column at which `token` starts in `line`.
+///
+/// `token` has to be a sub-slice of `line`, as returned by `split` or `trim`.
+pub fn column(line: &str, token: &str) -> usize {
+    let offset = token.as_ptr() as usize - line.as_ptr() as usize;
+    debug_assert!(offset <= line.len(), "token is not part of the line");
+    line[..offset].chars().count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_display_answers() {
+        assert_eq!(Answer::from(-7).to_string(), "-7");
+        assert_eq!(Answer::from(42usize).to_string(), "42");
+        assert_eq!(Answer::from(u64::MAX).to_string(), "18446744073709551615");
+    }
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec<u32>;
+
+        type Error = std::num::ParseIntError;
+
+        const INPUT: &'static str = "1\n2\n3";
+
+        fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+            input.lines().map(|line| line.parse()).collect()
+        }
+
+        fn part1(input: &Self::Input) -> Result<Answer, Self::Error> {
+            Ok(input.iter().sum::<u32>().into())
+        }
+
+        fn part2(input: &Self::Input) -> Result<Answer, Self::Error> {
+            Ok(input.iter().product::<u32>().into())
+        }
+    }
+
+    #[test]
+    fn should_locate_tokens() {
+        let input = "\n  12 ab\n\nc,d\n";
+        let lines: Vec<_> = numbered_lines(input).collect();
+        assert_eq!(lines, vec![(2, "  12 ab"), (4, "c,d")]);
+
+        let (_, line) = lines[0];
+        let tokens: Vec<_> = line
+            .split_whitespace()
+            .map(|token| column(line, token))
+            .collect();
+        assert_eq!(tokens, vec![3, 6]);
+        assert_eq!(column(lines[1].1, lines[1].1.split(',').nth(1).unwrap()), 3);
+    }
+
+    #[test]
+    fn should_parse_from_reader() {
+        let input = Sum::parse_reader("4\n5".as_bytes()).unwrap();
+        assert_eq!(Sum::part1(&input), Ok(Answer::Unsigned(9)));
+        assert_eq!(Sum::part2(&input), Ok(Answer::Unsigned(20)));
+
+        assert!(matches!(
+            Sum::parse_reader("4\nfive".as_bytes()),
+            Err(LoadError::Parse(_))
+        ));
+    }
+}
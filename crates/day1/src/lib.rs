@@ -1,3 +1,16 @@
+use aoc::{Answer, Solution};
+use thiserror::Error;
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum InputError {
+    #[error("line {line}, column {column}: '{value}' is not a depth")]
+    InvalidDepth {
+        line: usize,
+        column: usize,
+        value: String,
+    },
+}
+
 fn depth_increases(lines: &[i32]) -> usize {
     lines
         .windows(2)
@@ -15,30 +28,65 @@ fn depth_increases_triple(lines: &[i32]) -> usize {
         .count()
 }
 
-fn solve_pt_1() -> usize {
-    let input = include_str!("./input");
-    let lines: Vec<i32> = input
-        .trim()
-        .lines()
-        .map(|line| line.parse::<i32>().expect("input should be ints"))
-        .collect();
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<i32>;
+    type Error = InputError;
+
+    const INPUT: &'static str = include_str!("./input");
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        parse_input(input)
+    }
+
+    fn part1(lines: &Self::Input) -> Result<Answer, Self::Error> {
+        Ok(depth_increases(lines).into())
+    }
+
+    fn part2(lines: &Self::Input) -> Result<Answer, Self::Error> {
+        Ok(depth_increases_triple(lines).into())
+    }
+}
 
-    depth_increases(&lines)
+fn parse_input(input: &str) -> Result<Vec<i32>, InputError> {
+    aoc::numbered_lines(input)
+        .map(|(line_nr, line)| {
+            let value = line.trim();
+            value.parse::<i32>().map_err(|_| InputError::InvalidDepth {
+                line: line_nr,
+                column: aoc::column(line, value),
+                value: value.to_string(),
+            })
+        })
+        .collect()
 }
-fn solve_pt_2() -> usize {
-    let input = include_str!("./input");
-    let lines: Vec<i32> = input
-        .trim()
-        .lines()
-        .map(|line| line.parse::<i32>().expect("input should be ints"))
-        .collect();
 
-    depth_increases_triple(&lines)
+pub fn solve_pt_1() -> usize {
+    solve_pt_1_with_input(Day1::INPUT).expect("shipped input is valid")
+}
+
+pub fn solve_pt_1_with_input(input: &str) -> Result<usize, InputError> {
+    let lines = parse_input(input)?;
+
+    Ok(depth_increases(&lines))
+}
+
+pub fn solve_pt_2() -> usize {
+    solve_pt_2_with_input(Day1::INPUT).expect("shipped input is valid")
+}
+
+pub fn solve_pt_2_with_input(input: &str) -> Result<usize, InputError> {
+    let lines = parse_input(input)?;
+
+    Ok(depth_increases_triple(&lines))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{depth_increases, depth_increases_triple, solve_pt_1, solve_pt_2};
+    use crate::{
+        depth_increases, depth_increases_triple, parse_input, solve_pt_1, solve_pt_2, InputError,
+    };
 
     #[test]
     fn it_works() {
@@ -47,6 +95,19 @@ mod tests {
         assert_eq!(depth_increases_triple(&input), 5);
     }
 
+    #[test]
+    fn should_report_bad_depths() {
+        assert_eq!(parse_input("199\n200\n"), Ok(vec![199, 200]));
+        assert_eq!(
+            parse_input("199\n\n  2x0\n"),
+            Err(InputError::InvalidDepth {
+                line: 3,
+                column: 3,
+                value: "2x0".to_string()
+            })
+        );
+    }
+
     #[test]
     fn print_day1() {
         println!("depth inceases {} times", solve_pt_1());
@@ -1,29 +1,144 @@
-#[derive(Debug, Eq, PartialEq, Copy, Clone)]
-enum Bracket {
-    Round,
-    Square,
-    Curly,
-    Angle,
+use std::io::{self, BufRead};
+use std::sync::Arc;
+
+use aoc::{Answer, Solution};
+use thiserror::Error;
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum InputError {
+    #[error("line {line}, column {column}: '{char}' is not a bracket")]
+    InvalidCharacter {
+        line: usize,
+        column: usize,
+        char: char,
+    },
+    #[error("no line is incomplete, so there is no middle completion score")]
+    NoIncompleteLines,
 }
 
-impl Bracket {
-    fn corruption_score(&self) -> u64 {
-        match self {
-            Bracket::Round => 3,
-            Bracket::Square => 57,
-            Bracket::Curly => 1197,
-            Bracket::Angle => 25137
+/// An opener and its closer with their scores, both delimiters can be longer than a character
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BracketPair {
+    pub opener: String,
+    pub closer: String,
+    /// The points for a corrupted line whose first wrong closer is this one
+    pub corruption_score: u64,
+    /// The points for every closer of this pair that completes an incomplete line
+    pub completion_score: u64,
+}
+
+impl BracketPair {
+    pub fn new(opener: &str, closer: &str, corruption_score: u64, completion_score: u64) -> Self {
+        BracketPair {
+            opener: opener.to_string(),
+            closer: closer.to_string(),
+            corruption_score,
+            completion_score,
         }
     }
+}
 
-    fn completion_score(&self) -> u64 {
-        match self {
-            Bracket::Round => 1,
-            Bracket::Square => 2,
-            Bracket::Curly => 3,
-            Bracket::Angle => 4
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum BracketSetError {
+    #[error("a bracket set needs at least one pair")]
+    Empty,
+    #[error("openers and closers cannot be empty")]
+    EmptyDelimiter,
+    #[error("'{0}' is used as a delimiter more than once")]
+    DuplicateDelimiter(String),
+}
+
+/// The pairs of brackets that chunks are made of
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BracketSet {
+    pairs: Vec<BracketPair>,
+}
+
+impl BracketSet {
+    /// Every opener and closer has to be unique, so that a delimiter is never ambiguous
+    pub fn new(pairs: impl IntoIterator<Item = BracketPair>) -> Result<Self, BracketSetError> {
+        let pairs: Vec<_> = pairs.into_iter().collect();
+        if pairs.is_empty() {
+            return Err(BracketSetError::Empty);
+        }
+
+        let mut delimiters: Vec<&str> = Vec::new();
+        for pair in &pairs {
+            for delimiter in [pair.opener.as_str(), pair.closer.as_str()] {
+                if delimiter.is_empty() {
+                    return Err(BracketSetError::EmptyDelimiter);
+                }
+                if delimiters.contains(&delimiter) {
+                    return Err(BracketSetError::DuplicateDelimiter(delimiter.to_string()));
+                }
+                delimiters.push(delimiter);
+            }
+        }
+        Ok(BracketSet { pairs })
+    }
+
+    pub fn pair(&self, bracket: Bracket) -> &BracketPair {
+        &self.pairs[bracket.0]
+    }
+
+    fn delimiter(&self, token: Tokens) -> &str {
+        match token {
+            Tokens::Open(bracket) => &self.pair(bracket).opener,
+            Tokens::Close(bracket) => &self.pair(bracket).closer,
         }
     }
+
+    fn longest_delimiter(&self) -> usize {
+        self.pairs
+            .iter()
+            .map(|pair| pair.opener.len().max(pair.closer.len()))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The token that `rest` starts with and its length in bytes, the longest delimiter wins
+    fn token_at(&self, rest: &[u8]) -> Option<(Tokens, usize)> {
+        self.pairs
+            .iter()
+            .enumerate()
+            .flat_map(|(index, pair)| {
+                [
+                    (Tokens::Open(Bracket(index)), &pair.opener),
+                    (Tokens::Close(Bracket(index)), &pair.closer),
+                ]
+            })
+            .filter(|(_, delimiter)| rest.starts_with(delimiter.as_bytes()))
+            .map(|(token, delimiter)| (token, delimiter.len()))
+            .max_by_key(|&(_, length)| length)
+    }
+}
+
+impl Default for BracketSet {
+    /// The four brackets of the puzzle
+    fn default() -> Self {
+        BracketSet::new([
+            BracketPair::new("(", ")", 3, 1),
+            BracketPair::new("[", "]", 57, 2),
+            BracketPair::new("{", "}", 1197, 3),
+            BracketPair::new("<", ">", 25137, 4),
+        ])
+        .expect("the default brackets are unique")
+    }
+}
+
+/// A kind of bracket, by the index of its pair in the [`BracketSet`]
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Bracket(pub usize);
+
+impl Bracket {
+    /// `()` in the default set
+    pub const ROUND: Bracket = Bracket(0);
+    /// `[]` in the default set
+    pub const SQUARE: Bracket = Bracket(1);
+    /// `{}` in the default set
+    pub const CURLY: Bracket = Bracket(2);
+    /// `<>` in the default set
+    pub const ANGLE: Bracket = Bracket(3);
 }
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
@@ -32,126 +147,823 @@ enum Tokens {
     Close(Bracket),
 }
 
-impl From<char> for Tokens {
-
-    fn from(value: char) -> Self {
-        use Tokens::*;
-        use Bracket::*;
-        match value {
-            '(' => Open(Round),
-            '[' => Open(Square),
-            '{' => Open(Curly),
-            '<' => Open(Angle),
-            ')' => Close(Round),
-            ']' => Close(Square),
-            '}' => Close(Curly),
-            '>' => Close(Angle),
-                _ => unreachable!("level input only has brackets")
-        }
-    }
+/// A token and the byte offset in the line where it starts
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+struct Token {
+    kind: Tokens,
+    offset: usize,
 }
 
+pub struct Chunk {
+    line: usize,
+    source: String,
+    tokens: Vec<Token>,
+    brackets: Arc<BracketSet>,
+}
 
-struct Chunk(Vec<Tokens>);
+impl Chunk {
+    fn from_line(line_nr: usize, line: &str) -> Result<Self, InputError> {
+        Chunk::from_line_with(line_nr, line, Arc::new(BracketSet::default()))
+    }
 
-impl From<String> for Chunk {
-    fn from(input: String) -> Self {
-        Self(input.chars().map(|char| char.into()).collect())
+    /// Splits a line into the brackets of the set, surrounding whitespace is ignored
+    pub fn from_line_with(
+        line_nr: usize,
+        line: &str,
+        brackets: Arc<BracketSet>,
+    ) -> Result<Self, InputError> {
+        let mut tokens = Vec::new();
+        let mut rest = line.trim_start();
+        let mut offset = line.len() - rest.len();
+        rest = rest.trim_end();
+        while let Some(char) = rest.chars().next() {
+            let (kind, length) =
+                brackets
+                    .token_at(rest.as_bytes())
+                    .ok_or_else(|| InputError::InvalidCharacter {
+                        line: line_nr,
+                        column: aoc::column(line, rest),
+                        char,
+                    })?;
+            tokens.push(Token { kind, offset });
+            offset += length;
+            rest = &rest[length..];
+        }
+        Ok(Chunk {
+            line: line_nr,
+            source: line.to_string(),
+            tokens,
+            brackets,
+        })
     }
 }
 
 #[derive(Debug, Eq, PartialEq)]
-enum ParseError {
-    Corrupted(Bracket),
+pub enum ParseError {
+    /// A closer that doesn't belong to the innermost open bracket
+    Corrupted {
+        /// The byte offset of the closer in the line
+        offset: usize,
+        expected: Bracket,
+        found: Bracket,
+        /// The byte offset of the opener that `found` should have closed
+        opener: usize,
+    },
+    /// The brackets that are still open at the end of the line, innermost last
     Incomplete(Vec<Bracket>),
-    Broken
+    /// A closer while no bracket is open
+    Broken { offset: usize, found: Bracket },
 }
 
 impl ParseError {
-    fn corruption_points(&self) -> u64 {
+    pub fn corruption_points(&self, brackets: &BracketSet) -> u64 {
         match self {
-            ParseError::Corrupted(bracket) => bracket.corruption_score(),
-            _ => 0
+            ParseError::Corrupted { found, .. } => brackets.pair(*found).corruption_score,
+            _ => 0,
         }
     }
-    fn completion_points(&self) -> Option<u64> {
+
+    pub fn completion_points(&self, brackets: &BracketSet) -> Option<u64> {
         match self {
-            ParseError::Incomplete(brackets) => {
-                Some(brackets.iter().rfold(0, |acc, bracket| acc*5 + bracket.completion_score()))
-            }
-            _ => None
+            ParseError::Incomplete(stack) => Some(completion_points(stack, brackets)),
+            _ => None,
         }
     }
 }
 
+/// The points for closing the brackets of `stack`, innermost last, one after the other
+fn completion_points(stack: &[Bracket], brackets: &BracketSet) -> u64 {
+    stack.iter().rfold(0, |acc, bracket| {
+        acc * 5 + brackets.pair(*bracket).completion_score
+    })
+}
+
+/// How the recovering parser got back in sync after a wrong closer
+#[derive(Debug, Eq, PartialEq)]
+pub enum Resync {
+    /// The closer was dropped, because none of the open brackets matches it
+    Skipped,
+    /// The closer of this bracket was inserted before it, so that it closes the next bracket
+    Inserted(Bracket),
+}
+
+/// Everything that is wrong with a line, as found by [`Chunk::parse_recovering`]
+#[derive(Debug, Eq, PartialEq)]
+pub struct Recovered {
+    /// Every wrong closer and how it was dealt with, in the order of the line
+    pub corruptions: Vec<(ParseError, Resync)>,
+    /// The brackets that are still open at the end of the line, innermost last
+    pub unclosed: Vec<Bracket>,
+}
+
+impl Recovered {
+    pub fn is_valid(&self) -> bool {
+        self.corruptions.is_empty() && self.unclosed.is_empty()
+    }
+
+    /// The sum of the points of all corruptions
+    pub fn corruption_points(&self, brackets: &BracketSet) -> u64 {
+        self.corruptions
+            .iter()
+            .map(|(error, _)| error.corruption_points(brackets))
+            .sum()
+    }
+
+    pub fn completion_points(&self, brackets: &BracketSet) -> u64 {
+        completion_points(&self.unclosed, brackets)
+    }
+}
+
 impl Chunk {
-    fn parse(&self) -> Result<(), ParseError> {
-        let mut stack: Vec<Bracket> = vec![];
-        for bracket in &self.0 {
-            match bracket {
-                Tokens::Open(bracket) => stack.push(*bracket),
-                Tokens::Close(bracket) => {
-                    if *bracket != stack.pop().ok_or(ParseError::Broken)? {
-                        return Err(ParseError::Corrupted(*bracket))
+    pub fn parse(&self) -> Result<(), ParseError> {
+        let mut stack: Vec<(Bracket, usize)> = vec![];
+        for token in &self.tokens {
+            match token.kind {
+                Tokens::Open(bracket) => stack.push((bracket, token.offset)),
+                Tokens::Close(found) => {
+                    let (expected, opener) = stack.pop().ok_or(ParseError::Broken {
+                        offset: token.offset,
+                        found,
+                    })?;
+                    if found != expected {
+                        return Err(ParseError::Corrupted {
+                            offset: token.offset,
+                            expected,
+                            found,
+                            opener,
+                        });
                     }
                 }
             }
         }
 
         if !stack.is_empty() {
-            Err(ParseError::Incomplete(stack))
+            Err(ParseError::Incomplete(
+                stack.into_iter().map(|(bracket, _)| bracket).collect(),
+            ))
         } else {
             Ok(())
         }
     }
+
+    fn corruption_points(&self) -> u64 {
+        self.parse()
+            .err()
+            .map_or(0, |error| error.corruption_points(&self.brackets))
+    }
+
+    fn completion_points(&self) -> Option<u64> {
+        self.parse()
+            .err()
+            .and_then(|error| error.completion_points(&self.brackets))
+    }
+
+    /// Parses the whole line instead of stopping at the first wrong closer.
+    ///
+    /// When a wrong closer matches the bracket below the innermost one, the missing closer is
+    /// inserted before it, any other wrong closer is skipped.
+    pub fn parse_recovering(&self) -> Recovered {
+        let mut stack: Vec<(Bracket, usize)> = vec![];
+        let mut corruptions = vec![];
+        for token in &self.tokens {
+            let found = match token.kind {
+                Tokens::Open(bracket) => {
+                    stack.push((bracket, token.offset));
+                    continue;
+                }
+                Tokens::Close(found) => found,
+            };
+            match stack.last() {
+                Some(&(expected, _)) if expected == found => {
+                    stack.pop();
+                }
+                Some(&(expected, opener)) => {
+                    let error = ParseError::Corrupted {
+                        offset: token.offset,
+                        expected,
+                        found,
+                        opener,
+                    };
+                    let below = stack.len().checked_sub(2).map(|index| stack[index].0);
+                    if below == Some(found) {
+                        stack.truncate(stack.len() - 2);
+                        corruptions.push((error, Resync::Inserted(expected)));
+                    } else {
+                        corruptions.push((error, Resync::Skipped));
+                    }
+                }
+                None => corruptions.push((
+                    ParseError::Broken {
+                        offset: token.offset,
+                        found,
+                    },
+                    Resync::Skipped,
+                )),
+            }
+        }
+
+        Recovered {
+            corruptions,
+            unclosed: stack.into_iter().map(|(bracket, _)| bracket).collect(),
+        }
+    }
+
+    /// The brackets the line was split into
+    pub fn brackets(&self) -> &BracketSet {
+        &self.brackets
+    }
+
+    /// A rustc-style report of why the line doesn't parse, with carets under the culprits
+    pub fn diagnostic(&self) -> Option<String> {
+        let error = self.parse().err()?;
+        let pair = |bracket: Bracket| self.brackets.pair(bracket);
+        let column = |offset: usize| self.source[..offset].chars().count();
+        let width = |delimiter: &str| delimiter.chars().count();
+
+        let (offset, title, annotations) = match error {
+            ParseError::Corrupted {
+                offset,
+                expected,
+                found,
+                opener,
+            } => {
+                let opener_column = column(opener);
+                let opener_width = width(&pair(expected).opener);
+                let marker = format!(
+                    "{}{}{}{} expected `{}`",
+                    " ".repeat(opener_column),
+                    "-".repeat(opener_width),
+                    " ".repeat(column(offset) - opener_column - opener_width),
+                    "^".repeat(width(&pair(found).closer)),
+                    pair(expected).closer
+                );
+                let indent = " ".repeat(opener_column);
+                (
+                    offset,
+                    format!(
+                        "expected `{}`, found `{}`",
+                        pair(expected).closer,
+                        pair(found).closer
+                    ),
+                    vec![
+                        marker,
+                        format!("{}|", indent),
+                        format!("{}unclosed `{}`", indent, pair(expected).opener),
+                    ],
+                )
+            }
+            ParseError::Broken { offset, found } => (
+                offset,
+                format!("unexpected `{}`", pair(found).closer),
+                vec![format!(
+                    "{}{} no bracket to close",
+                    " ".repeat(column(offset)),
+                    "^".repeat(width(&pair(found).closer))
+                )],
+            ),
+            ParseError::Incomplete(stack) => {
+                let offset = self.source.trim_end().len();
+                let completion: String = stack
+                    .iter()
+                    .rev()
+                    .map(|bracket| pair(*bracket).closer.as_str())
+                    .collect();
+                (
+                    offset,
+                    "incomplete line".to_string(),
+                    vec![format!(
+                        "{}^ expected `{}`",
+                        " ".repeat(column(offset)),
+                        completion
+                    )],
+                )
+            }
+        };
+
+        let gutter = " ".repeat(self.line.to_string().len());
+        let mut report = format!(
+            "error: {}\n{}--> line {}, column {}\n{} |\n{} | {}\n",
+            title,
+            gutter,
+            self.line,
+            column(offset) + 1,
+            gutter,
+            self.line,
+            self.source
+        );
+        for annotation in annotations {
+            report.push_str(&format!("{} | {}\n", gutter, annotation));
+        }
+        Some(report)
+    }
 }
 
+/// A change to a line, at a byte offset of the original line
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Edit {
+    Insert {
+        offset: usize,
+        text: String,
+    },
+    Delete {
+        offset: usize,
+        text: String,
+    },
+    Replace {
+        offset: usize,
+        from: String,
+        to: String,
+    },
+}
 
-fn solve_pt_1() -> u64 {
-    let input = include_str!("input");
-    solve_pt_1_with_input(input)
+/// A line that parses and the edits that lead to it
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Repair {
+    pub line: String,
+    pub edits: Vec<Edit>,
 }
 
-fn solve_pt_1_with_input(input: &str) -> u64 {
-    parse_to_chunks(input).iter().flat_map(|chunk| chunk.parse().err()).map(|parse_error| parse_error.corruption_points()).sum()
+/// How the first token of a range ends up in a balanced line
+#[derive(Debug, Copy, Clone)]
+enum Choice {
+    Delete,
+    /// It opens a pair that the token at the index closes
+    Match(usize, Bracket),
+    /// It opens a pair that a closer inserted before the token at the index closes
+    InsertCloser(usize, Bracket),
+    /// An opener is inserted before it, which the token at the index closes
+    InsertOpener(usize, Bracket),
 }
 
-fn solve_pt_2_with_input(input: &str) -> u64 {
+/// A token of the repaired line
+#[derive(Debug, Copy, Clone)]
+enum Step {
+    Keep(usize),
+    Delete(usize),
+    Replace(usize, Tokens),
+    /// A new token before the token at the index, or at the end of the line
+    Insert(usize, Tokens),
+}
+
+impl Chunk {
+    /// The line with as few edits as possible so that it parses.
+    ///
+    /// Incomplete lines get their completion appended, other lines are balanced by a search over
+    /// all ranges of tokens, which takes cubic time in the length of the line.
+    pub fn repair(&self) -> Repair {
+        let steps = match self.parse() {
+            Ok(()) => (0..self.tokens.len()).map(Step::Keep).collect(),
+            Err(ParseError::Incomplete(stack)) => (0..self.tokens.len())
+                .map(Step::Keep)
+                .chain(
+                    stack
+                        .into_iter()
+                        .rev()
+                        .map(|bracket| Step::Insert(self.tokens.len(), Tokens::Close(bracket))),
+                )
+                .collect(),
+            Err(_) => self.minimal_edit(),
+        };
+        self.apply(&steps)
+    }
+
+    /// The steps of a balanced line with the fewest inserts, deletes and replaces
+    fn minimal_edit(&self) -> Vec<Step> {
+        let n = self.tokens.len();
+        let mut costs = vec![0; (n + 1) * (n + 1)];
+        for length in 1..=n {
+            for i in 0..=n - length {
+                let j = i + length;
+                costs[i * (n + 1) + j] = self.best_choice(&costs, i, j).0;
+            }
+        }
+
+        let mut steps = Vec::new();
+        self.rebuild(&costs, 0, n, &mut steps);
+        steps
+    }
+
+    /// The cheapest way to balance the tokens `i..j`, given the costs of all shorter ranges
+    fn best_choice(&self, costs: &[usize], i: usize, j: usize) -> (usize, Choice) {
+        let n = self.tokens.len();
+        let cost = |from: usize, to: usize| costs[from * (n + 1) + to];
+        let opens = |index: usize| match self.tokens[index].kind {
+            Tokens::Open(bracket) => (0, bracket),
+            Tokens::Close(bracket) => (1, bracket),
+        };
+        let closes = |index: usize| match self.tokens[index].kind {
+            Tokens::Close(bracket) => (0, bracket),
+            Tokens::Open(bracket) => (1, bracket),
+        };
+
+        let mut best = (1 + cost(i + 1, j), Choice::Delete);
+        for k in i + 1..j {
+            let (open_cost, opener) = opens(i);
+            let (close_cost, closer) = closes(k);
+            let (pair_cost, bracket) = match (open_cost, close_cost) {
+                (0, 0) if opener == closer => (0, opener),
+                (0, _) => (1, opener),
+                (_, 0) => (1, closer),
+                _ => (2, opener),
+            };
+            let total = pair_cost + cost(i + 1, k) + cost(k + 1, j);
+            if total < best.0 {
+                best = (total, Choice::Match(k, bracket));
+            }
+        }
+        for k in i + 1..=j {
+            let (open_cost, bracket) = opens(i);
+            let total = open_cost + 1 + cost(i + 1, k) + cost(k, j);
+            if total < best.0 {
+                best = (total, Choice::InsertCloser(k, bracket));
+            }
+        }
+        for k in i..j {
+            let (close_cost, bracket) = closes(k);
+            let total = 1 + close_cost + cost(i, k) + cost(k + 1, j);
+            if total < best.0 {
+                best = (total, Choice::InsertOpener(k, bracket));
+            }
+        }
+        best
+    }
+
+    fn rebuild(&self, costs: &[usize], i: usize, j: usize, steps: &mut Vec<Step>) {
+        if i == j {
+            return;
+        }
+        let token = |index: usize, kind: Tokens| {
+            if self.tokens[index].kind == kind {
+                Step::Keep(index)
+            } else {
+                Step::Replace(index, kind)
+            }
+        };
+        match self.best_choice(costs, i, j).1 {
+            Choice::Delete => {
+                steps.push(Step::Delete(i));
+                self.rebuild(costs, i + 1, j, steps);
+            }
+            Choice::Match(k, bracket) => {
+                steps.push(token(i, Tokens::Open(bracket)));
+                self.rebuild(costs, i + 1, k, steps);
+                steps.push(token(k, Tokens::Close(bracket)));
+                self.rebuild(costs, k + 1, j, steps);
+            }
+            Choice::InsertCloser(k, bracket) => {
+                steps.push(token(i, Tokens::Open(bracket)));
+                self.rebuild(costs, i + 1, k, steps);
+                steps.push(Step::Insert(k, Tokens::Close(bracket)));
+                self.rebuild(costs, k, j, steps);
+            }
+            Choice::InsertOpener(k, bracket) => {
+                steps.push(Step::Insert(i, Tokens::Open(bracket)));
+                self.rebuild(costs, i, k, steps);
+                steps.push(token(k, Tokens::Close(bracket)));
+                self.rebuild(costs, k + 1, j, steps);
+            }
+        }
+    }
+
+    /// Writes the line that the steps describe, whitespace around the brackets is kept
+    fn apply(&self, steps: &[Step]) -> Repair {
+        let start = self.source.len() - self.source.trim_start().len();
+        let end = self.source.trim_end().len().max(start);
+        let offset = |index: usize| self.tokens.get(index).map_or(end, |token| token.offset);
+        let text = |kind: Tokens| self.brackets.delimiter(kind).to_string();
+
+        let mut line = self.source[..start].to_string();
+        let mut edits = Vec::new();
+        for &step in steps {
+            match step {
+                Step::Keep(index) => {
+                    line.push_str(self.brackets.delimiter(self.tokens[index].kind))
+                }
+                Step::Delete(index) => edits.push(Edit::Delete {
+                    offset: offset(index),
+                    text: text(self.tokens[index].kind),
+                }),
+                Step::Replace(index, kind) => {
+                    line.push_str(&text(kind));
+                    edits.push(Edit::Replace {
+                        offset: offset(index),
+                        from: text(self.tokens[index].kind),
+                        to: text(kind),
+                    });
+                }
+                Step::Insert(index, kind) => {
+                    line.push_str(&text(kind));
+                    edits.push(Edit::Insert {
+                        offset: offset(index),
+                        text: text(kind),
+                    });
+                }
+            }
+        }
+        line.push_str(&self.source[end..]);
+        Repair { line, edits }
+    }
+}
+
+/// The diagnostics of all lines that don't parse, separated by blank lines
+pub fn render_report(chunks: &[Chunk]) -> String {
+    chunks
+        .iter()
+        .filter_map(Chunk::diagnostic)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The outcome of validating one line of a stream
+#[derive(Debug, Eq, PartialEq)]
+pub enum Validation {
+    Valid,
+    Invalid(ParseError),
+    /// A byte that doesn't start any of the delimiters, at a byte offset of the line
+    UnknownByte {
+        offset: usize,
+        byte: u8,
+    },
+    /// An opener at this byte offset would have exceeded the maximum depth
+    TooDeep {
+        offset: usize,
+    },
+}
+
+/// Checks the lines of a reader one after the other without keeping them in memory.
+///
+/// Only a window as long as the longest delimiter and a stack of at most `max_depth` open
+/// brackets are kept. Like [`Chunk::from_line_with`], whitespace around the brackets is ignored
+/// and whitespace between them is not, blank lines are skipped.
+pub struct Validator<R> {
+    reader: R,
+    brackets: BracketSet,
+    max_depth: usize,
+    line: usize,
+    window: Vec<u8>,
+    eof: bool,
+}
+
+impl<R: BufRead> Validator<R> {
+    /// A validator for the default brackets, up to 1024 of which can be open at once
+    pub fn new(reader: R) -> Self {
+        Validator {
+            reader,
+            brackets: BracketSet::default(),
+            max_depth: 1024,
+            line: 0,
+            window: Vec::new(),
+            eof: false,
+        }
+    }
+
+    pub fn with_brackets(mut self, brackets: BracketSet) -> Self {
+        self.brackets = brackets;
+        self
+    }
+
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    fn next_byte(&mut self) -> io::Result<Option<u8>> {
+        let byte = match self.reader.fill_buf()? {
+            [] => return Ok(None),
+            [byte, ..] => *byte,
+        };
+        self.reader.consume(1);
+        Ok(Some(byte))
+    }
+
+    /// Validates the next line, `None` if it was blank and at the end of the input
+    fn validate_line(&mut self) -> io::Result<Option<Validation>> {
+        let longest = self.brackets.longest_delimiter();
+        let mut stack: Vec<(Bracket, usize)> = Vec::new();
+        let mut outcome = None;
+        let mut offset = 0;
+        let mut blank = true;
+        // the first whitespace after a bracket, which is only fine at the end of the line
+        let mut gap = None;
+        let mut ended = false;
+        self.line += 1;
+        self.window.clear();
+
+        loop {
+            while !ended && self.window.len() < longest {
+                match self.next_byte()? {
+                    None => {
+                        self.eof = true;
+                        ended = true;
+                    }
+                    Some(b'\n') => ended = true,
+                    Some(byte) => self.window.push(byte),
+                }
+            }
+            let byte = match self.window.first() {
+                Some(&byte) => byte,
+                None => break,
+            };
+            if outcome.is_some() || byte.is_ascii_whitespace() {
+                if outcome.is_none() && !blank && gap.is_none() {
+                    gap = Some(Validation::UnknownByte { offset, byte });
+                }
+                self.window.remove(0);
+                offset += 1;
+                continue;
+            }
+            if gap.is_some() {
+                outcome = gap.take();
+                continue;
+            }
+
+            blank = false;
+            let (kind, length) = match self.brackets.token_at(&self.window) {
+                Some(token) => token,
+                None => {
+                    outcome = Some(Validation::UnknownByte { offset, byte });
+                    continue;
+                }
+            };
+            match kind {
+                Tokens::Open(_) if stack.len() == self.max_depth => {
+                    outcome = Some(Validation::TooDeep { offset });
+                }
+                Tokens::Open(bracket) => stack.push((bracket, offset)),
+                Tokens::Close(found) => match stack.pop() {
+                    None => {
+                        outcome = Some(Validation::Invalid(ParseError::Broken { offset, found }))
+                    }
+                    Some((expected, opener)) if expected != found => {
+                        outcome = Some(Validation::Invalid(ParseError::Corrupted {
+                            offset,
+                            expected,
+                            found,
+                            opener,
+                        }))
+                    }
+                    Some(_) => {}
+                },
+            }
+            self.window.drain(..length);
+            offset += length;
+        }
+
+        if blank {
+            return Ok(None);
+        }
+        Ok(Some(outcome.unwrap_or_else(|| {
+            if stack.is_empty() {
+                Validation::Valid
+            } else {
+                Validation::Invalid(ParseError::Incomplete(
+                    stack.into_iter().map(|(bracket, _)| bracket).collect(),
+                ))
+            }
+        })))
+    }
+}
+
+impl<R: BufRead> Iterator for Validator<R> {
+    /// The line number and how it validated
+    type Item = io::Result<(usize, Validation)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.eof {
+            match self.validate_line() {
+                Ok(Some(validation)) => return Some(Ok((self.line, validation))),
+                Ok(None) => continue,
+                Err(error) => {
+                    self.eof = true;
+                    return Some(Err(error));
+                }
+            }
+        }
+        None
+    }
+}
+
+fn syntax_error_score(chunks: &[Chunk]) -> u64 {
+    chunks.iter().map(Chunk::corruption_points).sum()
+}
+
+fn middle_completion_score(chunks: &[Chunk]) -> Result<u64, InputError> {
     use itertools::Itertools;
-    let iter: Vec<_> = parse_to_chunks(input).iter().flat_map(|chunk| chunk.parse().err()).flat_map(|parse_error| parse_error.completion_points()).sorted().collect();
+    let iter: Vec<_> = chunks
+        .iter()
+        .flat_map(Chunk::completion_points)
+        .sorted()
+        .collect();
+
+    iter.get(iter.len() / 2)
+        .copied()
+        .ok_or(InputError::NoIncompleteLines)
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec<Chunk>;
+    type Error = InputError;
+
+    const INPUT: &'static str = include_str!("input");
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        parse_to_chunks(input)
+    }
+
+    fn part1(chunks: &Self::Input) -> Result<Answer, Self::Error> {
+        Ok(syntax_error_score(chunks).into())
+    }
+
+    fn part2(chunks: &Self::Input) -> Result<Answer, Self::Error> {
+        Ok(middle_completion_score(chunks)?.into())
+    }
+}
 
-    iter[iter.len()/2]
+pub fn solve_pt_1() -> u64 {
+    solve_pt_1_with_input(Day10::INPUT).expect("shipped input is valid")
 }
 
-fn parse_to_chunks(input: &str) -> Vec<Chunk> {
-    input.trim().lines().map(|line| Chunk::from(line.to_string())).collect()
+pub fn solve_pt_1_with_input(input: &str) -> Result<u64, InputError> {
+    Ok(syntax_error_score(&parse_to_chunks(input)?))
 }
 
-fn solve_pt_2() -> u64 {
-    let input = include_str!("input");
-    solve_pt_2_with_input(input)
+pub fn solve_pt_2_with_input(input: &str) -> Result<u64, InputError> {
+    middle_completion_score(&parse_to_chunks(input)?)
+}
+
+fn parse_to_chunks(input: &str) -> Result<Vec<Chunk>, InputError> {
+    aoc::numbered_lines(input)
+        .map(|(line_nr, line)| Chunk::from_line(line_nr, line))
+        .collect()
+}
+
+/// Splits every line of the input into the brackets of the set
+pub fn parse_to_chunks_with(input: &str, brackets: BracketSet) -> Result<Vec<Chunk>, InputError> {
+    let brackets = Arc::new(brackets);
+    aoc::numbered_lines(input)
+        .map(|(line_nr, line)| Chunk::from_line_with(line_nr, line, brackets.clone()))
+        .collect()
+}
+
+pub fn solve_pt_2() -> u64 {
+    solve_pt_2_with_input(Day10::INPUT).expect("shipped input is valid")
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::Bracket::*;
-    use crate::Tokens::*;
     use super::*;
 
+    const ROUND: Bracket = Bracket::ROUND;
+    const SQUARE: Bracket = Bracket::SQUARE;
+    const CURLY: Bracket = Bracket::CURLY;
+    const ANGLE: Bracket = Bracket::ANGLE;
+
+    fn chunk(line: &str) -> Chunk {
+        Chunk::from_line(1, line).expect("test input only has brackets")
+    }
+
     #[test]
     fn chunk_parsing() {
-        assert_eq!(Chunk(vec![Open(Round), Close(Round)]).parse(), Ok(()));
-        assert_eq!(Chunk(vec![Open(Round), Close(Angle)]).parse(), Err(ParseError::Corrupted(Angle)));
-        assert_eq!(Chunk::from("([])".to_string()).parse(), Ok(()));
-        assert_eq!(Chunk::from("{()()()}".to_string()).parse(), Ok(()));
-        assert_eq!(Chunk::from("[<>({}){}[([])<>]]".to_string()).parse(), Ok(()));
-        assert_eq!(Chunk::from("{([(<{}[<>[]}>{[]{[(<()>".to_string()).parse(), Err(ParseError::Corrupted(Curly)));
-        assert_eq!(Chunk::from("[({(<(())[]>[[{[]{<()<>>".to_string()).parse(), Err(ParseError::Incomplete(vec![Curly, Curly, Square, Square, Round, Curly, Round, Square])));
+        assert_eq!(chunk("()").parse(), Ok(()));
+        assert_eq!(
+            chunk("(>").parse(),
+            Err(ParseError::Corrupted {
+                offset: 1,
+                expected: ROUND,
+                found: ANGLE,
+                opener: 0
+            })
+        );
+        assert_eq!(chunk("([])").parse(), Ok(()));
+        assert_eq!(chunk("{()()()}").parse(), Ok(()));
+        assert_eq!(chunk("[<>({}){}[([])<>]]").parse(), Ok(()));
+        assert_eq!(
+            chunk("{([(<{}[<>[]}>{[]{[(<()>").parse(),
+            Err(ParseError::Corrupted {
+                offset: 12,
+                expected: SQUARE,
+                found: CURLY,
+                opener: 7
+            })
+        );
+        assert_eq!(
+            chunk("[({(<(())[]>[[{[]{<()<>>").parse(),
+            Err(ParseError::Incomplete(vec![
+                SQUARE, ROUND, CURLY, ROUND, SQUARE, SQUARE, CURLY, CURLY
+            ]))
+        );
     }
 
     #[test]
     fn completion_score() {
-        assert_eq!(ParseError::Incomplete(vec![Angle, Curly, Round, Square]).completion_points().expect("works"), 294);
+        assert_eq!(
+            ParseError::Incomplete(vec![ANGLE, CURLY, ROUND, SQUARE])
+                .completion_points(&BracketSet::default())
+                .expect("works"),
+            294
+        );
     }
 
     #[test]
@@ -166,10 +978,266 @@ mod tests {
 [<(<(<(<{}))><([]([]()
 <{([([[(<>()){}]>(<<{{
 <{([{{}}[<[[[<>{}]]]>[]]";
-        assert_eq!(solve_pt_1_with_input(input), 26397);
-        assert_eq!(solve_pt_2_with_input(input), 288957);
+        assert_eq!(solve_pt_1_with_input(input), Ok(26397));
+        assert_eq!(solve_pt_2_with_input(input), Ok(288957));
+    }
+
+    #[test]
+    fn should_parse_user_defined_brackets() {
+        let brackets = BracketSet::new([
+            BracketPair::new("«", "»", 10, 1),
+            BracketPair::new("/*", "*/", 20, 2),
+            BracketPair::new("/", "\\", 30, 3),
+        ])
+        .unwrap();
+        let chunks = parse_to_chunks_with("«/*/\\*/»\n«/*»\n«/*/", brackets).unwrap();
+
+        assert_eq!(chunks[0].parse(), Ok(()));
+        assert_eq!(
+            chunks[1].parse(),
+            Err(ParseError::Corrupted {
+                offset: 4,
+                expected: Bracket(1),
+                found: Bracket(0),
+                opener: 2
+            })
+        );
+        assert_eq!(syntax_error_score(&chunks), 10);
+        assert_eq!(chunks[2].completion_points(), Some((3 * 5 + 2) * 5 + 1));
+
+        assert_eq!(
+            parse_to_chunks_with("«*»", BracketSet::default()).err(),
+            Some(InputError::InvalidCharacter {
+                line: 1,
+                column: 1,
+                char: '«'
+            })
+        );
+        assert_eq!(
+            BracketSet::new([BracketPair::new("|", "|", 1, 1)]),
+            Err(BracketSetError::DuplicateDelimiter("|".to_string()))
+        );
+        assert_eq!(BracketSet::new([]), Err(BracketSetError::Empty));
     }
 
+    #[test]
+    fn should_render_diagnostics() {
+        let chunks = parse_to_chunks("()\n{([(<{}[<>[]}>{[]{[(<()>\n  ())\n[(").unwrap();
+        assert_eq!(
+            chunks[2].parse(),
+            Err(ParseError::Broken {
+                offset: 4,
+                found: ROUND
+            })
+        );
+        assert_eq!(
+            render_report(&chunks),
+            "\
+error: expected `]`, found `}`
+ --> line 2, column 13
+  |
+2 | {([(<{}[<>[]}>{[]{[(<()>
+  |        -    ^ expected `]`
+  |        |
+  |        unclosed `[`
+
+error: unexpected `)`
+ --> line 3, column 5
+  |
+3 |   ())
+  |     ^ no bracket to close
+
+error: incomplete line
+ --> line 4, column 3
+  |
+4 | [(
+  |   ^ expected `)]`
+"
+        );
+    }
+
+    #[test]
+    fn should_recover_from_wrong_closers() {
+        let recovered = chunk("{([(<{}[<>[]}>{[]{[(<()>").parse_recovering();
+        assert_eq!(
+            recovered.corruptions,
+            vec![
+                (
+                    ParseError::Corrupted {
+                        offset: 12,
+                        expected: SQUARE,
+                        found: CURLY,
+                        opener: 7
+                    },
+                    Resync::Skipped
+                ),
+                (
+                    ParseError::Corrupted {
+                        offset: 13,
+                        expected: SQUARE,
+                        found: ANGLE,
+                        opener: 7
+                    },
+                    Resync::Inserted(SQUARE)
+                ),
+            ]
+        );
+        assert_eq!(
+            recovered.unclosed,
+            vec![CURLY, ROUND, SQUARE, ROUND, CURLY, CURLY, SQUARE, ROUND]
+        );
+        assert_eq!(
+            recovered.corruption_points(&BracketSet::default()),
+            1197 + 25137
+        );
+
+        let recovered = chunk("(]))").parse_recovering();
+        assert_eq!(recovered.corruptions.len(), 2);
+        assert_eq!(
+            recovered.corruptions[1],
+            (
+                ParseError::Broken {
+                    offset: 3,
+                    found: ROUND
+                },
+                Resync::Skipped
+            )
+        );
+        assert!(recovered.unclosed.is_empty());
+        assert!(chunk("[()]").parse_recovering().is_valid());
+    }
+
+    #[test]
+    fn should_repair_lines() {
+        let repair = chunk("[({(<(())[]>[[{[]{<()<>>").repair();
+        assert_eq!(repair.line, "[({(<(())[]>[[{[]{<()<>>}}]])})]");
+        assert_eq!(repair.edits.len(), 8);
+        assert_eq!(
+            repair.edits[0],
+            Edit::Insert {
+                offset: 24,
+                text: "}".to_string()
+            }
+        );
+
+        let repair = chunk(" (]").repair();
+        assert_eq!(repair.line, " ()");
+        assert_eq!(
+            repair.edits,
+            vec![Edit::Replace {
+                offset: 2,
+                from: "]".to_string(),
+                to: ")".to_string()
+            }]
+        );
+        assert_eq!(chunk("())").repair().edits.len(), 1);
+        assert_eq!(chunk("([)]").repair().edits.len(), 2);
+        assert_eq!(chunk("<>").repair().edits, vec![]);
+
+        let input = "{([(<{}[<>[]}>{[]{[(<()>\n)(\n[<(<(<(<{}))><([]([]()";
+        for chunk in parse_to_chunks(input).unwrap() {
+            let repair = chunk.repair();
+            let repaired = Chunk::from_line(1, &repair.line).unwrap();
+            assert_eq!(repaired.parse(), Ok(()), "{}", repair.line);
+            assert!(!repair.edits.is_empty());
+        }
+    }
+
+    #[test]
+    fn should_validate_streams() {
+        let corrupted = "{([(<{}[<>[]}>{[]{[(<()>";
+        let incomplete = "[({(<(())[]>[[{[]{<()<>>";
+        let input = format!("{}\n\n{}\n ()\t\r\n[x]\n( )\n", incomplete, corrupted);
+        let results: Vec<_> = Validator::new(input.as_bytes())
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(
+            results,
+            vec![
+                (
+                    1,
+                    Validation::Invalid(chunk(incomplete).parse().unwrap_err())
+                ),
+                (
+                    3,
+                    Validation::Invalid(chunk(corrupted).parse().unwrap_err())
+                ),
+                (4, Validation::Valid),
+                (
+                    5,
+                    Validation::UnknownByte {
+                        offset: 1,
+                        byte: b'x'
+                    }
+                ),
+                (
+                    6,
+                    Validation::UnknownByte {
+                        offset: 1,
+                        byte: b' '
+                    }
+                ),
+            ]
+        );
+        assert_eq!(
+            Chunk::from_line(6, "( )").err(),
+            Some(InputError::InvalidCharacter {
+                line: 6,
+                column: 2,
+                char: ' '
+            })
+        );
+
+        let brackets = BracketSet::new([
+            BracketPair::new("«", "»", 10, 1),
+            BracketPair::new("/*", "*/", 20, 2),
+        ])
+        .unwrap();
+        let results: Vec<_> = Validator::new("«/**/»\n«/*»\n/*«\n«/*«".as_bytes())
+            .with_brackets(brackets)
+            .with_max_depth(2)
+            .map(|result| result.unwrap().1)
+            .collect();
+        assert_eq!(
+            results,
+            vec![
+                Validation::Valid,
+                Validation::Invalid(ParseError::Corrupted {
+                    offset: 4,
+                    expected: Bracket(1),
+                    found: Bracket(0),
+                    opener: 2
+                }),
+                Validation::Invalid(ParseError::Incomplete(vec![Bracket(1), Bracket(0)])),
+                Validation::TooDeep { offset: 4 },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_inputs_without_incomplete_lines() {
+        assert_eq!(
+            solve_pt_2_with_input(""),
+            Err(InputError::NoIncompleteLines)
+        );
+        assert_eq!(
+            solve_pt_2_with_input("{([(<{}[<>[]}>{[]{[(<()>"),
+            Err(InputError::NoIncompleteLines)
+        );
+        assert_eq!(solve_pt_1_with_input("{([(<{}[<>[]}>{[]{[(<()>"), Ok(1197));
+    }
+
+    #[test]
+    fn should_report_invalid_characters() {
+        assert_eq!(
+            parse_to_chunks("([])\n{()x()}").err(),
+            Some(InputError::InvalidCharacter {
+                line: 2,
+                column: 4,
+                char: 'x'
+            })
+        );
+    }
 
     #[test]
     fn print_day_10() {
@@ -1,3 +1,45 @@
+use aoc::{Answer, Solution};
+use grid::{Grid, Neighbourhood};
+use thiserror::Error;
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum InputError {
+    #[error("line {line}, column {column}: '{char}' is not an energy level")]
+    InvalidEnergy {
+        line: usize,
+        column: usize,
+        char: char,
+    },
+    #[error("line {line}: expected {expected} octopuses but found {found}")]
+    UnevenRow {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[error("the cave is empty")]
+    Empty,
+}
+
+impl From<grid::ParseError> for InputError {
+    fn from(error: grid::ParseError) -> Self {
+        match error {
+            grid::ParseError::InvalidCell { line, column, char } => {
+                InputError::InvalidEnergy { line, column, char }
+            }
+            grid::ParseError::UnevenRow {
+                line,
+                expected,
+                found,
+            } => InputError::UnevenRow {
+                line,
+                expected,
+                found,
+            },
+            grid::ParseError::Empty => InputError::Empty,
+        }
+    }
+}
+
 #[derive(Default, Copy, Clone, Debug)]
 struct Octopus {
     level: u32,
@@ -47,111 +89,77 @@ impl Octopus {
     }
 }
 
-struct Cave {
-    grid: [[Octopus; 12]; 12],
+/// A rectangular cave of octopuses, of any size
+#[derive(Clone)]
+pub struct Cave {
+    grid: Grid<Octopus>,
     flashes: u32,
 }
 
 impl Cave {
-    fn new(input: &str) -> Self {
-        let mut grid = [[Octopus::default(); 12]; 12];
-        let raw: Vec<Vec<_>> = input
-            .trim()
-            .lines()
-            .map(|line| {
-                line.trim()
-                    .chars()
-                    .map(|c| c.to_digit(10).expect("only digits in input"))
-                    .collect()
-            })
-            .collect();
+    pub fn new(input: &str) -> Result<Self, InputError> {
+        let grid = Grid::parse(input, |c| c.to_digit(10).map(Octopus::new))?;
 
-        for x in 1..11 {
-            for y in 1..11 {
-                grid[x][y] = Octopus::new(raw[x - 1][y - 1]);
-            }
-        }
+        Ok(Self { grid, flashes: 0 })
+    }
+
+    /// The width and height of the cave
+    pub fn dim(&self) -> (usize, usize) {
+        self.grid.dim()
+    }
 
-        Self { grid, flashes: 0 }
+    /// The number of flashes since the cave was created
+    pub fn flashes(&self) -> u32 {
+        self.flashes
     }
 
-    fn step(&mut self) {
-        self.grid = self.grid.map(|row| row.map(|octopus| octopus.step()));
+    pub fn step(&mut self) {
+        self.grid = self.grid.map(|octopus| octopus.step());
     }
 
     fn flash(&mut self) -> bool {
         let mut flashed = false;
-        for x in 1..11 {
-            for y in 1..11 {
-                let octopus = &mut self.grid[x][y];
-                octopus.prime();
-                if octopus.try_flash() {
-                    flashed = true;
-                    self.flashes += 1;
-
-                    self.grid[x - 1][y - 1].excite();
-                    self.grid[x - 1][y].excite();
-                    self.grid[x - 1][y + 1].excite();
-                    self.grid[x][y - 1].excite();
-                    self.grid[x][y + 1].excite();
-                    self.grid[x + 1][y - 1].excite();
-                    self.grid[x + 1][y].excite();
-                    self.grid[x + 1][y + 1].excite();
+        for position in self.grid.positions() {
+            let octopus = &mut self.grid[position];
+            octopus.prime();
+            if octopus.try_flash() {
+                flashed = true;
+                self.flashes += 1;
+
+                for neighbour in self.grid.neighbours(position, Neighbourhood::Eight) {
+                    self.grid[neighbour].excite();
                 }
             }
         }
         flashed
     }
 
-    fn flash_until_settled(&mut self) -> bool {
-        let mut nr_of_rounds = 0;
-        while self.flash() {
-            nr_of_rounds += 1;
-            if nr_of_rounds > 100 {
-                eprintln!("Round took more than 100 flashes");
-                eprintln!("{:?}", self.grid);
-                break;
-            }
-        }
+    /// Flashes until no octopus is primed anymore and tells if all of them flashed.
+    ///
+    /// Every octopus flashes at most once per step, so this takes at most one round per octopus.
+    pub fn flash_until_settled(&mut self) -> bool {
+        while self.flash() {}
         self.is_synchronized()
     }
 
-    fn is_synchronized(&self) -> bool {
-        self.grid.iter().as_slice()[1..11]
-            .iter()
-            .all(|row| {
-                row.iter().as_slice()[1..11]
-                    .iter()
-                    .all(|octopus| octopus.level == 0)
-            })
+    pub fn is_synchronized(&self) -> bool {
+        self.grid.iter().all(|octopus| octopus.level == 0)
     }
 
-    fn print(&self) -> String {
-        self.grid.iter().as_slice()[1..11]
-            .iter()
-            .map(|row| {
-                let s: String = row.iter().as_slice()[1..11]
-                    .iter()
-                    .map(|octopus| octopus.level.to_string())
-                    .collect();
-                format!("{}\n", s)
-            })
-            .collect()
+    pub fn print(&self) -> String {
+        self.grid.render(|octopus| octopus.level.to_string())
     }
 }
 
-fn solve_pt_1() -> u32 {
-    let input = include_str!("input");
-    let mut cave = Cave::new(input);
+fn flashes_after_100_steps(mut cave: Cave) -> u32 {
     for _ in 0..100 {
         cave.step();
         cave.flash_until_settled();
     }
     cave.flashes
 }
-fn solve_pt_2() -> u32 {
-    let input = include_str!("input");
-    let mut cave = Cave::new(input);
+
+fn first_synchronized_step(mut cave: Cave) -> u32 {
     let mut i = 0;
     loop  {
         i += 1;
@@ -162,6 +170,43 @@ fn solve_pt_2() -> u32 {
     }
 }
 
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Cave;
+    type Error = InputError;
+
+    const INPUT: &'static str = include_str!("input");
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        Cave::new(input)
+    }
+
+    fn part1(cave: &Self::Input) -> Result<Answer, Self::Error> {
+        Ok(flashes_after_100_steps(cave.clone()).into())
+    }
+
+    fn part2(cave: &Self::Input) -> Result<Answer, Self::Error> {
+        Ok(first_synchronized_step(cave.clone()).into())
+    }
+}
+
+pub fn solve_pt_1() -> u32 {
+    solve_pt_1_with_input(Day11::INPUT).expect("shipped input is valid")
+}
+
+pub fn solve_pt_1_with_input(input: &str) -> Result<u32, InputError> {
+    Ok(flashes_after_100_steps(Cave::new(input)?))
+}
+
+pub fn solve_pt_2() -> u32 {
+    solve_pt_2_with_input(Day11::INPUT).expect("shipped input is valid")
+}
+
+pub fn solve_pt_2_with_input(input: &str) -> Result<u32, InputError> {
+    Ok(first_synchronized_step(Cave::new(input)?))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -179,7 +224,7 @@ mod tests {
 6882881134
 4846848554
 5283751526";
-        let mut cave = Cave::new(input);
+        let mut cave = Cave::new(input).unwrap();
 
         cave.step();
         cave.flash_until_settled();
@@ -228,7 +273,7 @@ mod tests {
 6882881134
 4846848554
 5283751526";
-        let mut cave = Cave::new(input);
+        let mut cave = Cave::new(input).unwrap();
         for _ in 0..10 {
             cave.step();
             cave.flash_until_settled();
@@ -249,7 +294,7 @@ mod tests {
 6882881134
 4846848554
 5283751526";
-        let mut cave = Cave::new(input);
+        let mut cave = Cave::new(input).unwrap();
         let mut i = 0;
         let sync = loop  {
             i += 1;
@@ -262,6 +307,83 @@ mod tests {
         assert_eq!(sync,  195);
     }
 
+    #[test]
+    fn should_report_malformed_caves() {
+        let input = "5483143223
+2745854711
+5264556173
+6141336146
+6357385478
+4167524645
+2176841721
+6882881134
+4846848554
+5283751526";
+        assert_eq!(
+            Cave::new(&input.replace("4167524645", "41675x4645")).err(),
+            Some(InputError::InvalidEnergy {
+                line: 6,
+                column: 6,
+                char: 'x'
+            })
+        );
+        assert_eq!(
+            Cave::new(&input.replace("4167524645", "416752464")).err(),
+            Some(InputError::UnevenRow {
+                line: 6,
+                expected: 10,
+                found: 9
+            })
+        );
+        assert_eq!(Cave::new("\n\n").err(), Some(InputError::Empty));
+    }
+
+    #[test]
+    fn should_flash_in_small_caves() {
+        let mut cave = Cave::new(
+            "11111
+19991
+19191
+19991
+11111",
+        )
+        .unwrap();
+
+        cave.step();
+        cave.flash_until_settled();
+        assert_eq!(cave.print(), "34543\n40004\n50005\n40004\n34543\n");
+        assert_eq!(cave.flashes(), 9);
+    }
+
+    #[test]
+    fn should_flash_in_non_square_caves() {
+        fn transpose(rows: &str) -> String {
+            let rows: Vec<_> = rows.lines().collect();
+            (0..rows[0].len())
+                .map(|column| {
+                    let transposed: String = rows.iter().map(|row| &row[column..=column]).collect();
+                    format!("{}\n", transposed)
+                })
+                .collect()
+        }
+
+        let input = "5483143223\n2745854711\n5264556173";
+        let mut wide = Cave::new(input).unwrap();
+        let mut tall = Cave::new(&transpose(input)).unwrap();
+        assert_eq!(wide.dim(), (10, 3));
+        assert_eq!(tall.dim(), (3, 10));
+
+        for _ in 0..20 {
+            wide.step();
+            tall.step();
+            assert_eq!(wide.flash_until_settled(), tall.flash_until_settled());
+        }
+
+        assert_eq!(transpose(&wide.print()), tall.print());
+        assert_eq!(wide.flashes(), tall.flashes());
+        assert!(wide.flashes() > 0);
+    }
+
     #[test]
     fn print_day_11() {
         println!("The number of flashes after 100 loops is {}", solve_pt_1());
@@ -1,4 +1,25 @@
-enum Direction {
+use aoc::{Answer, Solution};
+use thiserror::Error;
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum InputError {
+    #[error("line {line}, column {column}: direction '{direction}' not found")]
+    UnknownDirection {
+        line: usize,
+        column: usize,
+        direction: String,
+    },
+    #[error("line {line}, column {column}: expected an amount")]
+    MissingAmount { line: usize, column: usize },
+    #[error("line {line}, column {column}: '{amount}' is not an amount")]
+    InvalidAmount {
+        line: usize,
+        column: usize,
+        amount: String,
+    },
+}
+
+pub enum Direction {
     Forward(i32),
     Up(i32),
     Down(i32),
@@ -11,23 +32,31 @@ struct Position {
     aim: i32,
 }
 
-fn parse_input(lines: &str) -> Vec<Direction> {
-    lines
-        .trim()
-        .lines()
-        .map(|line| {
+fn parse_input(lines: &str) -> Result<Vec<Direction>, InputError> {
+    aoc::numbered_lines(lines)
+        .map(|(line_nr, line)| {
             let mut splits = line.split_whitespace();
-            let direction = splits.next().expect("no direction");
-            let amount = splits
-                .next()
-                .expect("no amount")
+            let direction = splits.next().expect("line has content");
+            let amount = splits.next().ok_or(InputError::MissingAmount {
+                line: line_nr,
+                column: aoc::column(line, direction) + direction.len(),
+            })?;
+            let amount = amount
                 .parse::<i32>()
-                .expect("input should be ints");
+                .map_err(|_| InputError::InvalidAmount {
+                    line: line_nr,
+                    column: aoc::column(line, amount),
+                    amount: amount.to_string(),
+                })?;
             match direction {
-                "forward" => Direction::Forward(amount),
-                "down" => Direction::Down(amount),
-                "up" => Direction::Up(amount),
-                unknown => panic!("direction '{}' not found", unknown),
+                "forward" => Ok(Direction::Forward(amount)),
+                "down" => Ok(Direction::Down(amount)),
+                "up" => Ok(Direction::Up(amount)),
+                unknown => Err(InputError::UnknownDirection {
+                    line: line_nr,
+                    column: aoc::column(line, unknown),
+                    direction: unknown.to_string(),
+                }),
             }
         })
         .collect()
@@ -62,22 +91,51 @@ fn calculate_position_with_aim(lines: &[Direction]) -> Position {
     position
 }
 
-fn solve_pt_1() -> i32 {
-    let input = include_str!("./input");
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<Direction>;
+    type Error = InputError;
+
+    const INPUT: &'static str = include_str!("./input");
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        parse_input(input)
+    }
+
+    fn part1(lines: &Self::Input) -> Result<Answer, Self::Error> {
+        let position = calculate_position(lines);
+
+        Ok((position.depth * position.horizontal).into())
+    }
+
+    fn part2(lines: &Self::Input) -> Result<Answer, Self::Error> {
+        let position = calculate_position_with_aim(lines);
 
-    let parsed = parse_input(input);
+        Ok((position.depth * position.horizontal).into())
+    }
+}
+
+pub fn solve_pt_1() -> i32 {
+    solve_pt_1_with_input(Day2::INPUT).expect("shipped input is valid")
+}
+
+pub fn solve_pt_1_with_input(input: &str) -> Result<i32, InputError> {
+    let parsed = parse_input(input)?;
     let position = calculate_position(&parsed);
 
-    position.depth * position.horizontal
+    Ok(position.depth * position.horizontal)
 }
 
-fn solve_pt_2() -> i32 {
-    let input = include_str!("./input");
+pub fn solve_pt_2() -> i32 {
+    solve_pt_2_with_input(Day2::INPUT).expect("shipped input is valid")
+}
 
-    let parsed = parse_input(input);
+pub fn solve_pt_2_with_input(input: &str) -> Result<i32, InputError> {
+    let parsed = parse_input(input)?;
     let position = calculate_position_with_aim(&parsed);
 
-    position.depth * position.horizontal
+    Ok(position.depth * position.horizontal)
 }
 
 #[cfg(test)]
@@ -92,7 +150,7 @@ forward 8
 up 3
 down 8
 forward 2";
-        let parsed = parse_input(input);
+        let parsed = parse_input(input).unwrap();
         let position = calculate_position(&parsed);
         assert_eq!(position.depth, 10);
         assert_eq!(position.horizontal, 15);
@@ -102,6 +160,30 @@ forward 2";
         assert_eq!(position.horizontal, 15);
     }
 
+    #[test]
+    fn should_report_malformed_commands() {
+        assert_eq!(
+            parse_input("forward 5\nbackward 3").err(),
+            Some(InputError::UnknownDirection {
+                line: 2,
+                column: 1,
+                direction: "backward".to_string()
+            })
+        );
+        assert_eq!(
+            parse_input("up").err(),
+            Some(InputError::MissingAmount { line: 1, column: 3 })
+        );
+        assert_eq!(
+            parse_input("down  x").err(),
+            Some(InputError::InvalidAmount {
+                line: 1,
+                column: 7,
+                amount: "x".to_string()
+            })
+        );
+    }
+
     #[test]
     fn print_day2() {
         println!("position hash is {}", solve_pt_1());
@@ -1,17 +1,58 @@
-fn parse_input(lines: &str) -> Vec<Vec<u32>> {
-    lines
-        .trim()
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|char| {
-                    char.to_string()
-                        .parse::<u32>()
-                        .expect("parsing should work")
+use aoc::{Answer, Solution};
+use thiserror::Error;
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum InputError {
+    #[error("line {line}, column {column}: '{char}' is not a bit")]
+    InvalidBit {
+        line: usize,
+        column: usize,
+        char: char,
+    },
+    #[error("line {line}: expected {expected} bits but found {found}")]
+    UnevenLength {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[error("the diagnostic report is empty")]
+    Empty,
+    #[error("the bit criteria don't narrow the report down to a single number")]
+    NoUniqueRating,
+}
+
+fn parse_input(lines: &str) -> Result<Vec<Vec<u32>>, InputError> {
+    let report = aoc::numbered_lines(lines)
+        .map(|(line_nr, line)| {
+            let start = aoc::column(line, line.trim_start());
+            let bits = line
+                .trim()
+                .chars()
+                .enumerate()
+                .map(|(index, char)| match char {
+                    '0' => Ok(0),
+                    '1' => Ok(1),
+                    _ => Err(InputError::InvalidBit {
+                        line: line_nr,
+                        column: start + index,
+                        char,
+                    }),
                 })
-                .collect::<Vec<_>>()
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok((line_nr, bits))
         })
-        .collect()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let expected = report.first().ok_or(InputError::Empty)?.1.len();
+    if let Some((line, bits)) = report.iter().find(|(_, bits)| bits.len() != expected) {
+        return Err(InputError::UnevenLength {
+            line: *line,
+            expected,
+            found: bits.len(),
+        });
+    }
+
+    Ok(report.into_iter().map(|(_, bits)| bits).collect())
 }
 
 fn get_gamma_epsilon(input: Vec<Vec<u32>>) -> (u32, u32) {
@@ -34,7 +75,10 @@ fn get_gamma_epsilon(input: Vec<Vec<u32>>) -> (u32, u32) {
     (gamma, epsilon)
 }
 
-fn get_life_support_read(input: Vec<Vec<u32>>, determine_keeper: fn(u32, u32) -> u32) -> Vec<u32> {
+fn get_life_support_read(
+    input: Vec<Vec<u32>>,
+    determine_keeper: fn(u32, u32) -> u32,
+) -> Result<Vec<u32>, InputError> {
     let len = input.len();
     let mut input = input;
 
@@ -46,16 +90,15 @@ fn get_life_support_read(input: Vec<Vec<u32>>, determine_keeper: fn(u32, u32) ->
 
         let dominant = determine_keeper(input.len() as u32, items);
 
-        input = input
-            .into_iter()
-            .filter(|vec| *vec.get(index).expect("should be in range") == dominant)
-            .collect();
-        if input.len() == 1 {
-            return input.get(0).unwrap().to_owned();
+        input.retain(|vec| *vec.get(index).expect("should be in range") == dominant);
+        match input.as_slice() {
+            [rating] => return Ok(rating.to_owned()),
+            [] => break,
+            _ => {}
         }
     }
 
-    panic!("filtering was not enough...")
+    Err(InputError::NoUniqueRating)
 }
 
 fn determine_keeper_oxygen(len: u32, items: u32) -> u32 {
@@ -82,26 +125,60 @@ fn convert_to_decimal(input: Vec<u32>) -> u32 {
     res
 }
 
-fn solve_pt_1() -> u32 {
-    let input = include_str!("./input");
-
-    let parsed = parse_input(input);
-    let (gamma, epsilon) = get_gamma_epsilon(parsed);
+fn power_consumption(parsed: &[Vec<u32>]) -> u32 {
+    let (gamma, epsilon) = get_gamma_epsilon(parsed.to_vec());
 
     gamma * epsilon
 }
 
-fn solve_pt_2() -> u32 {
-    let input = include_str!("./input");
-
-    let parsed = parse_input(input);
+fn life_support_rating(parsed: &[Vec<u32>]) -> Result<u32, InputError> {
     let oxygen = convert_to_decimal(get_life_support_read(
-        parsed.clone(),
+        parsed.to_vec(),
         determine_keeper_oxygen,
-    ));
-    let co2 = convert_to_decimal(get_life_support_read(parsed.clone(), determine_keeper_co2));
+    )?);
+    let co2 = convert_to_decimal(get_life_support_read(
+        parsed.to_vec(),
+        determine_keeper_co2,
+    )?);
 
-    oxygen * co2
+    Ok(oxygen * co2)
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Vec<Vec<u32>>;
+    type Error = InputError;
+
+    const INPUT: &'static str = include_str!("./input");
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        parse_input(input)
+    }
+
+    fn part1(parsed: &Self::Input) -> Result<Answer, Self::Error> {
+        Ok(power_consumption(parsed).into())
+    }
+
+    fn part2(parsed: &Self::Input) -> Result<Answer, Self::Error> {
+        Ok(life_support_rating(parsed)?.into())
+    }
+}
+
+pub fn solve_pt_1() -> u32 {
+    solve_pt_1_with_input(Day3::INPUT).expect("shipped input is valid")
+}
+
+pub fn solve_pt_1_with_input(input: &str) -> Result<u32, InputError> {
+    Ok(power_consumption(&parse_input(input)?))
+}
+
+pub fn solve_pt_2() -> u32 {
+    solve_pt_2_with_input(Day3::INPUT).expect("shipped input is valid")
+}
+
+pub fn solve_pt_2_with_input(input: &str) -> Result<u32, InputError> {
+    life_support_rating(&parse_input(input)?)
 }
 
 #[cfg(test)]
@@ -122,19 +199,20 @@ mod tests {
 11001
 00010
 01010";
-        let parsed = parse_input(input);
+        let parsed = parse_input(input).unwrap();
         let (gamma, epsilon) = get_gamma_epsilon(parsed.clone());
         assert_eq!(gamma, 22);
         assert_eq!(epsilon, 9);
 
-        let oxygen = convert_to_decimal(get_life_support_read(
-            parsed.clone(),
-            determine_keeper_oxygen,
-        ));
+        let oxygen = convert_to_decimal(
+            get_life_support_read(parsed.clone(), determine_keeper_oxygen).unwrap(),
+        );
 
         assert_eq!(oxygen, 23);
 
-        let co2 = convert_to_decimal(get_life_support_read(parsed.clone(), determine_keeper_co2));
+        let co2 = convert_to_decimal(
+            get_life_support_read(parsed.clone(), determine_keeper_co2).unwrap(),
+        );
 
         assert_eq!(co2, 10);
     }
@@ -145,6 +223,36 @@ mod tests {
         assert_eq!(convert_to_decimal(vec![0, 1, 1, 0]), 6);
     }
 
+    #[test]
+    fn should_report_malformed_reports() {
+        assert_eq!(
+            parse_input("0101\n0121"),
+            Err(InputError::InvalidBit {
+                line: 2,
+                column: 3,
+                char: '2'
+            })
+        );
+        assert_eq!(
+            parse_input("0101\n010"),
+            Err(InputError::UnevenLength {
+                line: 2,
+                expected: 4,
+                found: 3
+            })
+        );
+        assert_eq!(parse_input("\n"), Err(InputError::Empty));
+    }
+
+    #[test]
+    fn should_report_reports_without_unique_rating() {
+        assert_eq!(solve_pt_1_with_input("0101\n0101"), Ok(5 * 10));
+        assert_eq!(
+            solve_pt_2_with_input("0101\n0101"),
+            Err(InputError::NoUniqueRating)
+        );
+    }
+
     #[test]
     fn print_day3() {
         println!("The power consumption is {}", solve_pt_1());
@@ -1,4 +1,26 @@
+use aoc::{Answer, Solution};
 use itertools::Itertools;
+use thiserror::Error;
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum InputError {
+    #[error("line {line}, column {column}: '{value}' is not a number")]
+    InvalidNumber {
+        line: usize,
+        column: usize,
+        value: String,
+    },
+    #[error("line {line}: a board row needs 5 numbers but has {found}")]
+    WrongRowLength { line: usize, found: usize },
+    #[error("line {line}: a board needs 5 rows but has {found}")]
+    WrongBoardHeight { line: usize, found: usize },
+    #[error("the input has no draws")]
+    MissingDraws,
+    #[error("the input has no boards")]
+    MissingBoards,
+    #[error("no board wins with the drawn numbers")]
+    NobodyWins,
+}
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 enum Cell {
@@ -7,7 +29,7 @@ enum Cell {
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
-struct Board([[Cell; 5]; 5]);
+pub struct Board([[Cell; 5]; 5]);
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 struct WinningData {
@@ -42,7 +64,7 @@ impl Board {
         }
 
         for i in 0..5 {
-            let column_win = vec![
+            let column_win = [
                 self.0[0][i],
                 self.0[1][i],
                 self.0[2][i],
@@ -88,70 +110,149 @@ impl Board {
     }
 }
 
-fn parse_inputs(input: &str) -> Vec<u32> {
-    input
-        .trim()
-        .split(",")
-        .map(|line| line.parse::<u32>().expect("input should be ints"))
+fn parse_number(line_nr: usize, line: &str, item: &str) -> Result<u32, InputError> {
+    item.parse::<u32>().map_err(|_| InputError::InvalidNumber {
+        line: line_nr,
+        column: aoc::column(line, item),
+        value: item.to_string(),
+    })
+}
+
+fn parse_inputs(line_nr: usize, line: &str) -> Result<Vec<u32>, InputError> {
+    line.split(',')
+        .map(|item| parse_number(line_nr, line, item.trim()))
         .collect()
 }
 
-fn parse_boards(input: &str) -> Vec<Board> {
-    input
-        .trim()
-        .split("\n\n")
-        .map(|board| {
-            Board(
-                board
-                    .trim()
-                    .lines()
-                    .into_iter()
-                    .map(|line| {
-                        line.split_whitespace()
-                            .map(|item| {
-                                Cell::Empty(item.parse::<u32>().expect("input should be ints"))
-                            })
-                            .collect::<Vec<_>>()
-                            .try_into()
-                            .unwrap()
-                    })
-                    .collect::<Vec<_>>()
-                    .try_into()
-                    .unwrap(),
-            )
+fn parse_row(line_nr: usize, line: &str) -> Result<[Cell; 5], InputError> {
+    let row = line
+        .split_whitespace()
+        .map(|item| parse_number(line_nr, line, item).map(Cell::Empty))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    row.try_into()
+        .map_err(|row: Vec<_>| InputError::WrongRowLength {
+            line: line_nr,
+            found: row.len(),
         })
-        .collect()
 }
 
-fn solve_pt_1() -> u32 {
-    let input = include_str!("./inputs");
-    let inputs = parse_inputs(input);
-    let mut boards = parse_boards(include_str!("./boards"));
+/// Collects the rows of a board that started on the given line
+fn finish_board(board_start: usize, rows: Vec<[Cell; 5]>) -> Result<Board, InputError> {
+    rows.try_into()
+        .map(Board)
+        .map_err(|rows: Vec<_>| InputError::WrongBoardHeight {
+            line: board_start,
+            found: rows.len(),
+        })
+}
+
+/// The drawn numbers and the boards playing along
+pub struct Bingo {
+    draws: Vec<u32>,
+    boards: Vec<Board>,
+}
+
+/// Parses the draws on the first line and the boards below, separated by empty lines
+fn parse_bingo(input: &str) -> Result<Bingo, InputError> {
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .skip_while(|(_, line)| line.trim().is_empty());
+
+    let (line_nr, draws) = lines.next().ok_or(InputError::MissingDraws)?;
+    let draws = parse_inputs(line_nr, draws)?;
+
+    let mut boards = vec![];
+    let mut rows = vec![];
+    let mut board_start = 0;
+    for (line_nr, line) in lines {
+        if line.trim().is_empty() {
+            if !rows.is_empty() {
+                boards.push(finish_board(board_start, std::mem::take(&mut rows))?);
+            }
+            continue;
+        }
+        if rows.is_empty() {
+            board_start = line_nr;
+        }
+        rows.push(parse_row(line_nr, line)?);
+    }
+    if !rows.is_empty() {
+        boards.push(finish_board(board_start, rows)?);
+    }
+
+    if boards.is_empty() {
+        return Err(InputError::MissingBoards);
+    }
+    Ok(Bingo { draws, boards })
+}
+
+fn first_winning_score(bingo: &Bingo) -> Result<u32, InputError> {
+    let mut boards = bingo.boards.clone();
 
-    for draw in inputs {
+    for &draw in &bingo.draws {
         for board in &mut boards {
             board.mark_number(draw);
             if board.has_won() {
-                return board.count_unmarked() * draw;
+                return Ok(board.count_unmarked() * draw);
             }
         }
     }
 
-    panic!("nobody won :(");
+    Err(InputError::NobodyWins)
 }
 
-fn solve_pt_2() -> u32 {
-    let inputs = parse_inputs(include_str!("./inputs"));
-    let boards = parse_boards(include_str!("./boards"));
-
-    let winning_board_data = boards
-        .into_iter()
-        .filter_map(|mut board| board.play_until_win(&inputs))
+fn last_winning_score(bingo: &Bingo) -> Result<u32, InputError> {
+    let winning_board_data = bingo
+        .boards
+        .iter()
+        .filter_map(|&(mut board)| board.play_until_win(&bingo.draws))
         .sorted_by_key(|w| w.winning_move)
         .last()
-        .unwrap();
+        .ok_or(InputError::NobodyWins)?;
 
-    winning_board_data.final_draw * winning_board_data.empty_cells
+    Ok(winning_board_data.final_draw * winning_board_data.empty_cells)
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Bingo;
+    type Error = InputError;
+
+    const INPUT: &'static str = concat!(include_str!("./inputs"), "\n\n", include_str!("./boards"));
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        parse_bingo(input)
+    }
+
+    fn part1(bingo: &Self::Input) -> Result<Answer, Self::Error> {
+        Ok(first_winning_score(bingo)?.into())
+    }
+
+    fn part2(bingo: &Self::Input) -> Result<Answer, Self::Error> {
+        Ok(last_winning_score(bingo)?.into())
+    }
+}
+
+pub fn solve_pt_1() -> u32 {
+    solve_pt_1_with_input(Day4::INPUT).expect("shipped input is valid")
+}
+
+/// Solves part one for an input with the draws on the first line and the boards below
+pub fn solve_pt_1_with_input(input: &str) -> Result<u32, InputError> {
+    first_winning_score(&parse_bingo(input)?)
+}
+
+pub fn solve_pt_2() -> u32 {
+    solve_pt_2_with_input(Day4::INPUT).expect("shipped input is valid")
+}
+
+/// Solves part two for an input with the draws on the first line and the boards below
+pub fn solve_pt_2_with_input(input: &str) -> Result<u32, InputError> {
+    last_winning_score(&parse_bingo(input)?)
 }
 
 #[cfg(test)]
@@ -169,9 +270,9 @@ mod tests {
             [Empty(2), Empty(0), Empty(12), Empty(3), Empty(7)],
         ]);
 
-        for draw in vec![7, 4, 9, 5, 11, 17, 23, 2, 0, 14, 21] {
+        for draw in [7, 4, 9, 5, 11, 17, 23, 2, 0, 14, 21] {
             board.mark_number(draw);
-            assert_eq!(board.has_won(), false);
+            assert!(!board.has_won());
         }
         board.mark_number(24);
         assert!(board.has_won());
@@ -180,6 +281,59 @@ mod tests {
         assert_eq!(unmarked, 188)
     }
 
+    #[test]
+    fn should_report_malformed_boards() {
+        let boards = "1,2,x
+
+ 1  2  3  4  5
+ 6  7  8  9 10
+11 12 13 14 15
+16 17 18 19 20
+21 22 23 24 25";
+        assert_eq!(
+            parse_bingo(boards).err(),
+            Some(InputError::InvalidNumber {
+                line: 1,
+                column: 5,
+                value: "x".to_string()
+            })
+        );
+        assert_eq!(
+            parse_bingo(
+                &boards
+                    .replace("x", "3")
+                    .replace(" 6  7  8  9 10", " 6  7  8  9")
+            )
+            .err(),
+            Some(InputError::WrongRowLength { line: 4, found: 4 })
+        );
+        assert_eq!(
+            parse_bingo(&boards.replace("x", "3").replace("21 22 23 24 25", "")).err(),
+            Some(InputError::WrongBoardHeight { line: 3, found: 4 })
+        );
+        assert_eq!(
+            parse_bingo("1,2,3\n\n").err(),
+            Some(InputError::MissingBoards)
+        );
+    }
+
+    #[test]
+    fn should_report_games_without_winner() {
+        let bingo = "1,2,3
+
+ 1  2  4  5  6
+ 7  8  9 10 11
+12 13 14 15 16
+17 18 19 20 21
+22 23 24 25 26";
+        assert_eq!(solve_pt_1_with_input(bingo), Err(InputError::NobodyWins));
+        assert_eq!(solve_pt_2_with_input(bingo), Err(InputError::NobodyWins));
+        assert_eq!(
+            solve_pt_1_with_input(&bingo.replace("1,2,3", "1,2,3,4,5,6")),
+            Ok((7..=26).sum::<u32>() * 6)
+        );
+    }
+
     #[test]
     fn print_day_4() {
         println!("The final score on the winning board is {}", solve_pt_1());
@@ -188,5 +342,4 @@ mod tests {
             solve_pt_2()
         );
     }
-
 }
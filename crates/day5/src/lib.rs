@@ -1,41 +1,338 @@
-use ndarray::{s, Array2};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-struct Board(Array2<i32>);
+use aoc::{Answer, Solution};
+use grid::Grid;
+use peg::error::ParseError;
+use peg::str::LineCol;
+use thiserror::Error;
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum InputError {
+    #[error("line {line}, column {column}: expected {expected}")]
+    InvalidLine {
+        line: usize,
+        column: usize,
+        expected: String,
+    },
+    #[error("the input has no lines")]
+    Empty,
+}
+
+/// Counts how many lines cover every point
+trait Overlaps {
+    fn mark(&mut self, x: i32, y: i32);
+
+    /// The number of points that are covered by at least two lines
+    fn count_intersections(&self) -> usize;
+
+    fn mark_line(&mut self, line: &Line, rasterisation: Rasterisation) {
+        for (x, y) in line.points(rasterisation) {
+            self.mark(x, y);
+        }
+    }
+}
+
+/// A dense board that has a cell for every point between the origin and the lines
+struct Board(Grid<i32>);
 
 impl Board {
-    fn mark_horizontal(&mut self, line: &HorizontalLine) {
-        self.0
-            .slice_mut(s!(line.start_x..=line.end_x, line.y))
-            .map_inplace(|field| *field += 1);
+    /// The most cells a dense board may have, larger inputs are counted on a sparse board
+    const MAX_CELLS: usize = 1 << 24;
+
+    /// Creates an empty board that is large enough to hold all lines, as long as none of them
+    /// has negative coordinates and the board does not get too large
+    fn for_lines(lines: &[Line]) -> Option<Board> {
+        let coordinates = |line: &Line| [line.start_x, line.start_y, line.end_x, line.end_y];
+        if lines.iter().flat_map(coordinates).any(|c| c < 0) {
+            return None;
+        }
+
+        let max_x = lines
+            .iter()
+            .map(|line| line.end_x.max(line.start_x))
+            .max()? as usize;
+        let max_y = lines
+            .iter()
+            .map(|line| line.end_y.max(line.start_y))
+            .max()? as usize;
+
+        let cells = (max_x + 1).checked_mul(max_y + 1)?;
+        (cells <= Self::MAX_CELLS).then(|| Board(Grid::new(max_x + 1, max_y + 1)))
+    }
+}
+
+impl Overlaps for Board {
+    fn mark(&mut self, x: i32, y: i32) {
+        self.0[(x as usize, y as usize)] += 1;
+    }
+
+    fn count_intersections(&self) -> usize {
+        self.0.iter().filter(|&&field| field >= 2).count()
+    }
+}
+
+/// The directions of the lines that a sparse board keeps as intervals
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+enum Direction {
+    Horizontal,
+    Vertical,
+    /// From the top left to the bottom right
+    Diagonal,
+    /// From the bottom left to the top right
+    AntiDiagonal,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::Horizontal,
+        Direction::Vertical,
+        Direction::Diagonal,
+        Direction::AntiDiagonal,
+    ];
+
+    /// The direction of a line, `None` for sloped lines
+    fn of(line: &Line) -> Option<Direction> {
+        match line.kind() {
+            LineKind::Horizontal => Some(Direction::Horizontal),
+            LineKind::Vertical => Some(Direction::Vertical),
+            LineKind::Diagonal if (line.end_x > line.start_x) == (line.end_y > line.start_y) => {
+                Some(Direction::Diagonal)
+            }
+            LineKind::Diagonal => Some(Direction::AntiDiagonal),
+            LineKind::Sloped => None,
+        }
     }
 
-    fn mark_vertical(&mut self, line: &VerticalLine) {
-        self.0
-            .slice_mut(s!(line.x, line.start_y..=line.end_y))
-            .map_inplace(|field| *field += 1);
+    /// The step from one point to the next along the direction
+    fn step(&self) -> (i64, i64) {
+        match self {
+            Direction::Horizontal => (1, 0),
+            Direction::Vertical => (0, 1),
+            Direction::Diagonal => (1, 1),
+            Direction::AntiDiagonal => (1, -1),
+        }
     }
-    fn mark_diagonal(&mut self, line: &DiagonalLine) {
-        for increase in 0..=line.length {
-            let y_increase = if line.upward_sloping { increase } else { -increase };
-            let x = (line.start_x + increase) as usize;
-            let y = (line.start_y + y_increase) as usize;
-            self.0[(x, y)] += 1;
+
+    /// Which of the parallel lines in this direction passes through the point, and where on
+    /// that line the point lies
+    fn locate(&self, (x, y): (i64, i64)) -> (i64, i64) {
+        let (step_x, step_y) = self.step();
+        let position = if step_x == 0 { y } else { x };
+        (step_x * y - step_y * x, position)
+    }
+
+    /// The point with integer coordinates where two lines of different directions cross, if any
+    fn crossing(carrier: (Direction, i64), other: (Direction, i64)) -> Option<(i64, i64)> {
+        let ((step_x, step_y), offset) = (carrier.0.step(), carrier.1);
+        let ((other_step_x, other_step_y), other_offset) = (other.0.step(), other.1);
+        let determinant = step_x * other_step_y - step_y * other_step_x;
+        if determinant == 0 {
+            return None;
         }
+
+        let x = offset * other_step_x - step_x * other_offset;
+        let y = offset * other_step_y - step_y * other_offset;
+        (x % determinant == 0 && y % determinant == 0).then(|| (x / determinant, y / determinant))
     }
+}
+
+/// The parts of a line that segments on it cover, as sorted and disjoint intervals
+struct Coverage {
+    /// The intervals covered by at least one segment
+    once: Vec<(i64, i64)>,
+    /// The intervals covered by at least two segments
+    twice: Vec<(i64, i64)>,
+}
 
+impl Coverage {
+    /// Sweeps over the ends of the segments, so it takes time in the number of segments only
+    fn of(segments: &[(i64, i64)]) -> Coverage {
+        let mut ends: Vec<(i64, i32)> = segments
+            .iter()
+            .flat_map(|&(start, end)| [(start, 1), (end + 1, -1)])
+            .collect();
+        ends.sort_unstable();
+
+        let mut coverage = Coverage {
+            once: Vec::new(),
+            twice: Vec::new(),
+        };
+        let mut depth = 0;
+        for (position, change) in ends {
+            for (threshold, intervals) in [(1, &mut coverage.once), (2, &mut coverage.twice)] {
+                if depth < threshold && depth + change >= threshold {
+                    intervals.push((position, position));
+                } else if depth >= threshold && depth + change < threshold {
+                    intervals.last_mut().expect("an interval was opened").1 = position - 1;
+                }
+            }
+            depth += change;
+        }
+        coverage
+    }
+
+    /// The number of points covered by at least two segments
+    fn covered_twice(&self) -> i64 {
+        self.twice.iter().map(|(start, end)| end - start + 1).sum()
+    }
+}
+
+fn covers(intervals: &[(i64, i64)], position: i64) -> bool {
+    let index = intervals.partition_point(|&(_, end)| end < position);
+    intervals
+        .get(index)
+        .is_some_and(|&(start, _)| start <= position)
+}
+
+/// A board that only stores the lines, so it handles any coordinates and lengths.
+///
+/// Straight and diagonal lines are kept as intervals on the line they lie on, only the points
+/// of sloped lines are stored one by one.
+#[derive(Default)]
+struct SparseBoard {
+    segments: HashMap<(Direction, i64), Vec<(i64, i64)>>,
+    points: HashMap<(i32, i32), u32>,
+}
+
+impl Overlaps for SparseBoard {
+    fn mark(&mut self, x: i32, y: i32) {
+        *self.points.entry((x, y)).or_default() += 1;
+    }
+
+    fn mark_line(&mut self, line: &Line, rasterisation: Rasterisation) {
+        let Some(direction) = Direction::of(line) else {
+            for (x, y) in line.points(rasterisation) {
+                self.mark(x, y);
+            }
+            return;
+        };
+
+        let (carrier, start) = direction.locate((line.start_x.into(), line.start_y.into()));
+        let (_, end) = direction.locate((line.end_x.into(), line.end_y.into()));
+        self.segments
+            .entry((direction, carrier))
+            .or_default()
+            .push((start.min(end), start.max(end)));
+    }
+
+    /// Counts the points covered twice on every line, then corrects for the points where lines
+    /// of different directions cross and for the points of sloped lines
     fn count_intersections(&self) -> usize {
-        self.0.iter().filter(|&&field| field >= 2).count()
+        let coverages: HashMap<_, _> = self
+            .segments
+            .iter()
+            .map(|(&carrier, segments)| (carrier, Coverage::of(segments)))
+            .collect();
+        // how many lines cover a point at least once, and how many of them at least twice
+        let coverage_at = |point: (i64, i64)| {
+            Direction::ALL
+                .into_iter()
+                .filter_map(|direction| {
+                    let (carrier, position) = direction.locate(point);
+                    let coverage = coverages.get(&(direction, carrier))?;
+                    covers(&coverage.once, position).then(|| covers(&coverage.twice, position))
+                })
+                .fold((0, 0), |(once, twice), covered_twice| {
+                    (once + 1, twice + usize::from(covered_twice))
+                })
+        };
+
+        let mut count: i64 = coverages.values().map(Coverage::covered_twice).sum();
+
+        let carriers: Vec<_> = coverages.keys().copied().collect();
+        let mut crossings = HashSet::new();
+        for (index, &carrier) in carriers.iter().enumerate() {
+            for &other in &carriers[index + 1..] {
+                let Some(point) = Direction::crossing(carrier, other) else {
+                    continue;
+                };
+                let on_both = [carrier, other].into_iter().all(|(direction, _)| {
+                    let (carrier, position) = direction.locate(point);
+                    covers(&coverages[&(direction, carrier)].once, position)
+                });
+                if on_both {
+                    crossings.insert(point);
+                }
+            }
+        }
+        // a crossing counts once, however many of its lines already counted it
+        for &point in &crossings {
+            let (_, counted) = coverage_at(point);
+            count += 1 - counted as i64;
+        }
+
+        for (&(x, y), &lines) in &self.points {
+            let (once, twice) = coverage_at((x.into(), y.into()));
+            if twice == 0 && once < 2 && lines as usize + once >= 2 {
+                count += 1;
+            }
+        }
+        count as usize
     }
 }
 
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
-struct Line {
+pub struct Line {
     start_x: i32,
     start_y: i32,
     end_x: i32,
     end_y: i32,
 }
 
+/// The orientations a line can have
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LineKind {
+    Horizontal,
+    Vertical,
+    /// A diagonal at exactly 45 degrees
+    Diagonal,
+    /// Any other slope
+    Sloped,
+}
+
+/// How the points of a line are found
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Rasterisation {
+    /// Only the points with integer coordinates that lie exactly on the line
+    LatticePoints,
+    /// The points closest to the line, without gaps between them, as Bresenham draws it
+    Bresenham,
+}
+
+/// Which lines are drawn on the board
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Slopes {
+    /// Horizontal and vertical lines only
+    Straight,
+    /// Straight lines and diagonals at 45 degrees
+    Diagonal,
+    /// Lines of any slope, sloped ones rasterised as given
+    Any(Rasterisation),
+}
+
+impl Slopes {
+    /// How a line of the kind is drawn, `None` if it is not drawn at all
+    fn rasterisation(&self, kind: LineKind) -> Option<Rasterisation> {
+        match (self, kind) {
+            (_, LineKind::Horizontal | LineKind::Vertical) => Some(Rasterisation::LatticePoints),
+            (Slopes::Diagonal | Slopes::Any(_), LineKind::Diagonal) => {
+                Some(Rasterisation::LatticePoints)
+            }
+            (Slopes::Any(rasterisation), LineKind::Sloped) => Some(*rasterisation),
+            _ => None,
+        }
+    }
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
 impl Line {
     fn new(start_x: i32, start_y: i32, end_x: i32, end_y: i32) -> Line {
         Line {
@@ -45,91 +342,119 @@ impl Line {
             end_y,
         }
     }
-    fn to_vertical(self) -> Option<VerticalLine> {
-        let mut y = vec![self.start_y, self.end_y];
-        y.sort_unstable();
-        if self.start_x == self.end_x {
-            Some(VerticalLine {
-                x: self.start_x,
-                start_y: y[0],
-                end_y: y[1],
-            })
+
+    /// A line that is a single point counts as horizontal
+    pub fn kind(&self) -> LineKind {
+        let length_x = i64::from(self.end_x) - i64::from(self.start_x);
+        let length_y = i64::from(self.end_y) - i64::from(self.start_y);
+
+        if length_y == 0 {
+            LineKind::Horizontal
+        } else if length_x == 0 {
+            LineKind::Vertical
+        } else if length_x.abs() == length_y.abs() {
+            LineKind::Diagonal
         } else {
-            None
+            LineKind::Sloped
         }
     }
 
-    fn to_horizontal(self) -> Option<HorizontalLine> {
-        let mut x = vec![self.start_x, self.end_x];
-        x.sort_unstable();
-        if self.start_y == self.end_y {
-            Some(HorizontalLine {
-                y: self.start_y,
-                start_x: x[0],
-                end_x: x[1],
-            })
-        } else {
-            None
+    /// The points of the line from its start to its end.
+    ///
+    /// Both rasterisations give the same points for straight and diagonal lines.
+    pub fn points(&self, rasterisation: Rasterisation) -> Box<dyn Iterator<Item = (i32, i32)>> {
+        match rasterisation {
+            Rasterisation::LatticePoints => Box::new(self.lattice_points()),
+            Rasterisation::Bresenham => Box::new(self.bresenham_points()),
         }
     }
 
-    fn to_diagonal(self) -> Option<DiagonalLine> {
-        let length_x = self.end_x - self.start_x;
-        let length_y = self.end_y - self.start_y;
+    fn lattice_points(&self) -> impl Iterator<Item = (i32, i32)> {
+        let (start_x, start_y) = (i64::from(self.start_x), i64::from(self.start_y));
+        let length_x = i64::from(self.end_x) - start_x;
+        let length_y = i64::from(self.end_y) - start_y;
 
-        if length_x == length_y {
-            Some(DiagonalLine {
-                start_x: self.start_x.min(self.end_x),
-                start_y: self.start_y.min(self.end_y),
-                length: length_x.abs(),
-                upward_sloping: true,
-            })
-        } else if length_x == -length_y {
-            let (start_x, start_y) = if self.start_x < self.end_x {
-                (self.start_x, self.start_y)
+        let steps = gcd(length_x.into(), length_y.into()) as i64;
+        let (step_x, step_y) = (length_x / steps.max(1), length_y / steps.max(1));
+        (0..=steps).map(move |step| {
+            let x = start_x + step * step_x;
+            let y = start_y + step * step_y;
+            (x as i32, y as i32)
+        })
+    }
+
+    fn bresenham_points(&self) -> impl Iterator<Item = (i32, i32)> {
+        let (start_x, start_y) = (i64::from(self.start_x), i64::from(self.start_y));
+        let (end_x, end_y) = (i64::from(self.end_x), i64::from(self.end_y));
+        let (distance_x, step_x) = ((end_x - start_x).abs(), (end_x - start_x).signum());
+        let (distance_y, step_y) = (-(end_y - start_y).abs(), (end_y - start_y).signum());
+
+        let mut next = Some((start_x, start_y, distance_x + distance_y));
+        std::iter::from_fn(move || {
+            let (mut x, mut y, mut error) = next?;
+            let point = (x as i32, y as i32);
+            next = if (x, y) == (end_x, end_y) {
+                None
             } else {
-                (self.end_x, self.end_y)
+                let doubled_error = 2 * error;
+                if doubled_error >= distance_y {
+                    error += distance_y;
+                    x += step_x;
+                }
+                if doubled_error <= distance_x {
+                    error += distance_x;
+                    y += step_y;
+                }
+                Some((x, y, error))
             };
-
-            Some(DiagonalLine {
-                start_x,
-                start_y,
-                length: length_x.abs(),
-                upward_sloping: false,
-            })
-        } else {
-            None
-        }
+            Some(point)
+        })
     }
-}
 
-#[derive(Eq, PartialEq, Copy, Clone, Debug)]
-struct VerticalLine {
-    x: i32,
-    start_y: i32,
-    end_y: i32,
-}
+    /// The part of the line that lies within `0..width` and `0..height`, with its ends rounded
+    /// to the nearest points, found as Liang and Barsky do
+    fn clipped(&self, width: usize, height: usize) -> Option<Line> {
+        let (start_x, start_y) = (f64::from(self.start_x), f64::from(self.start_y));
+        let length_x = f64::from(self.end_x) - start_x;
+        let length_y = f64::from(self.end_y) - start_y;
+        let (max_x, max_y) = (width.checked_sub(1)? as f64, height.checked_sub(1)? as f64);
 
-#[derive(Eq, PartialEq, Copy, Clone, Debug)]
-struct HorizontalLine {
-    y: i32,
-    start_x: i32,
-    end_x: i32,
-}
+        let (mut first, mut last) = (0.0f64, 1.0f64);
+        for (direction, distance) in [
+            (-length_x, start_x),
+            (length_x, max_x - start_x),
+            (-length_y, start_y),
+            (length_y, max_y - start_y),
+        ] {
+            if direction == 0.0 {
+                if distance < 0.0 {
+                    return None;
+                }
+            } else if direction < 0.0 {
+                first = first.max(distance / direction);
+            } else {
+                last = last.min(distance / direction);
+            }
+        }
+        if first > last {
+            return None;
+        }
 
-#[derive(Eq, PartialEq, Copy, Clone, Debug)]
-struct DiagonalLine {
-    start_x: i32,
-    start_y: i32,
-    length: i32,
-    upward_sloping: bool,
+        let point = |t: f64| {
+            let x = (start_x + t * length_x).round().clamp(0.0, max_x);
+            let y = (start_y + t * length_y).round().clamp(0.0, max_y);
+            (x as i32, y as i32)
+        };
+        let ((start_x, start_y), (end_x, end_y)) = (point(first), point(last));
+        Some(Line::new(start_x, start_y, end_x, end_y))
+    }
 }
 
-fn parse_line(s: &str) -> anyhow::Result<Line> {
+fn parse_line(s: &str) -> Result<Line, ParseError<LineCol>> {
     peg::parser! {
           grammar parser() for str {
                 rule number() -> i32
-                  = n:$(['0'..='9']+) { n.parse().unwrap() }
+                  = n:$("-"? ['0'..='9']+) {? n.parse().or(Err("a number that fits into an i32")) }
 
                 pub(crate) rule line() -> Line
                     = start_x:number() "," start_y:number() " -> " end_x:number() "," end_y:number() {
@@ -138,72 +463,475 @@ fn parse_line(s: &str) -> anyhow::Result<Line> {
         }
     }
 
-    Ok(parser::line(s)?)
+    parser::line(s)
+}
+
+/// A point whose coordinates are fractions with a shared, positive denominator
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ExactPoint {
+    pub x: i128,
+    pub y: i128,
+    pub denominator: i128,
 }
 
-fn solve_pt_1() -> usize {
-    let input = include_str!("input");
-    let (lines, mut board) = parse(input);
+impl ExactPoint {
+    fn new(x: i128, y: i128, denominator: i128) -> Self {
+        let divisor = gcd(gcd(x, y), denominator) * denominator.signum();
+        ExactPoint {
+            x: x / divisor,
+            y: y / divisor,
+            denominator: denominator / divisor,
+        }
+    }
 
-    add_straight_intersections(lines, &mut board);
-    board.count_intersections()
+    fn lattice(x: i32, y: i32) -> Self {
+        ExactPoint::new(x.into(), y.into(), 1)
+    }
+
+    /// The point as cell coordinates, if it lies on one
+    pub fn to_lattice(&self) -> Option<(i32, i32)> {
+        (self.denominator == 1).then_some((self.x as i32, self.y as i32))
+    }
 }
 
-fn solve_pt_2() -> usize {
-    let input = include_str!("input");
-    let (lines, mut board) = parse(input);
+/// Where two lines meet
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Intersection {
+    /// The lines cross or touch in a single point, which need not lie on a cell
+    Point(ExactPoint),
+    /// The lines lie on top of each other along this segment
+    Segment(Line),
+}
 
-    add_straight_intersections(lines.clone(), &mut board);
-    add_diagonal_intersections(lines, &mut board);
-    board.count_intersections()
+fn cross(a: (i128, i128), b: (i128, i128)) -> i128 {
+    a.0 * b.1 - a.1 * b.0
 }
 
-fn parse(input: &str) -> (Vec<Line>, Board) {
-    use ndarray::prelude::*;
-    let lines: Vec<_> = input
-        .trim()
-        .lines()
-        .map(|line| parse_line(line).expect("parsing failed"))
-        .collect();
-    let max_x = lines
-        .iter()
-        .max_by_key(|l| l.end_x.max(l.start_x))
-        .map(|line| line.end_x.max(line.start_x))
-        .expect("we have a line") as usize;
-    let max_y = lines
-        .iter()
-        .max_by_key(|l| l.end_y.max(l.start_y))
-        .map(|line| line.end_y.max(line.start_y))
-        .expect("we have a line") as usize;
+impl Line {
+    fn start(&self) -> (i128, i128) {
+        (self.start_x.into(), self.start_y.into())
+    }
+
+    /// The vector from the start to the end of the line
+    fn direction(&self) -> (i128, i128) {
+        let (start_x, start_y) = self.start();
+        (
+            i128::from(self.end_x) - start_x,
+            i128::from(self.end_y) - start_y,
+        )
+    }
 
-    (
-        lines,
-        Board(Array2::default((max_x + 1, max_y + 1).into_shape())),
-    )
+    /// Whether the point lies on the line, including its ends
+    fn contains(&self, (x, y): (i32, i32)) -> bool {
+        let (start_x, start_y) = self.start();
+        let offset = (i128::from(x) - start_x, i128::from(y) - start_y);
+        cross(self.direction(), offset) == 0
+            && (self.start_x.min(self.end_x)..=self.start_x.max(self.end_x)).contains(&x)
+            && (self.start_y.min(self.end_y)..=self.start_y.max(self.end_y)).contains(&y)
+    }
+
+    /// Where the two lines meet, `None` if they do not meet at all
+    pub fn intersection(&self, other: &Line) -> Option<Intersection> {
+        let (direction, other_direction) = (self.direction(), other.direction());
+        if direction == (0, 0) {
+            let point = (self.start_x, self.start_y);
+            return other
+                .contains(point)
+                .then(|| Intersection::Point(ExactPoint::lattice(point.0, point.1)));
+        }
+        if other_direction == (0, 0) {
+            return other.intersection(self);
+        }
+
+        let (start, other_start) = (self.start(), other.start());
+        let offset = (other_start.0 - start.0, other_start.1 - start.1);
+        let denominator = cross(direction, other_direction);
+
+        if denominator == 0 {
+            return if cross(offset, direction) == 0 {
+                self.collinear_overlap(other)
+            } else {
+                None
+            };
+        }
+
+        // the lines cross at start + t * direction, with t = numerator / denominator
+        let (numerator, other_numerator) =
+            (cross(offset, other_direction), cross(offset, direction));
+        let (numerator, other_numerator, denominator) = if denominator < 0 {
+            (-numerator, -other_numerator, -denominator)
+        } else {
+            (numerator, other_numerator, denominator)
+        };
+        let on_both =
+            (0..=denominator).contains(&numerator) && (0..=denominator).contains(&other_numerator);
+
+        on_both.then(|| {
+            Intersection::Point(ExactPoint::new(
+                start.0 * denominator + numerator * direction.0,
+                start.1 * denominator + numerator * direction.1,
+                denominator,
+            ))
+        })
+    }
+
+    /// The part that two lines on the same straight have in common
+    fn collinear_overlap(&self, other: &Line) -> Option<Intersection> {
+        let start = self.start();
+        let direction = self.direction();
+        let position = |(x, y): (i32, i32)| {
+            (i128::from(x) - start.0) * direction.0 + (i128::from(y) - start.1) * direction.1
+        };
+
+        let mut ends = [
+            (self.start_x, self.start_y),
+            (self.end_x, self.end_y),
+            (other.start_x, other.start_y),
+            (other.end_x, other.end_y),
+        ];
+        ends[..2].sort_by_key(|&end| position(end));
+        ends[2..].sort_by_key(|&end| position(end));
+
+        let first = [ends[0], ends[2]]
+            .into_iter()
+            .max_by_key(|&end| position(end))?;
+        let last = [ends[1], ends[3]]
+            .into_iter()
+            .min_by_key(|&end| position(end))?;
+        match position(first).cmp(&position(last)) {
+            Ordering::Less => Some(Intersection::Segment(Line::new(
+                first.0, first.1, last.0, last.1,
+            ))),
+            Ordering::Equal => Some(Intersection::Point(ExactPoint::lattice(first.0, first.1))),
+            Ordering::Greater => None,
+        }
+    }
 }
 
-fn add_straight_intersections(lines: Vec<Line>, board: &mut Board) {
-    let horizontal_lines: Vec<_> = lines
-        .clone()
-        .into_iter()
-        .filter_map(Line::to_horizontal)
-        .collect();
-    let vertical_lines: Vec<_> = lines.into_iter().filter_map(Line::to_vertical).collect();
+/// Lists for every cell that is covered by at least two lines which lines cover it, by their
+/// index in `lines`.
+///
+/// Compares every pair of lines, so it does not need to walk the cells of all lines.
+/// Lines cover the cells that lie exactly on them, as with [`Rasterisation::LatticePoints`].
+pub fn contributing_lines(lines: &[Line]) -> BTreeMap<(i32, i32), Vec<usize>> {
+    let mut cells: BTreeMap<(i32, i32), Vec<usize>> = BTreeMap::new();
+    for (index, line) in lines.iter().enumerate() {
+        for (other_index, other) in lines.iter().enumerate().skip(index + 1) {
+            let shared_cells = match line.intersection(other) {
+                Some(Intersection::Point(point)) => Box::new(point.to_lattice().into_iter()),
+                Some(Intersection::Segment(segment)) => {
+                    segment.points(Rasterisation::LatticePoints)
+                }
+                None => Box::new(std::iter::empty()),
+            };
+            for cell in shared_cells {
+                let contributors = cells.entry(cell).or_default();
+                for index in [index, other_index] {
+                    if !contributors.contains(&index) {
+                        contributors.push(index);
+                    }
+                }
+            }
+        }
+    }
+    for contributors in cells.values_mut() {
+        contributors.sort_unstable();
+    }
+    cells
+}
+
+/// The overlap counts of the area that the lines cover, to be exported as an image
+pub struct Heatmap {
+    /// The point in the top left corner
+    origin: (i32, i32),
+    counts: Grid<u32>,
+}
+
+impl Overlaps for Heatmap {
+    fn mark(&mut self, x: i32, y: i32) {
+        let (origin_x, origin_y) = self.origin;
+        self.counts[((x - origin_x) as usize, (y - origin_y) as usize)] += 1;
+    }
+
+    fn count_intersections(&self) -> usize {
+        self.counts.iter().filter(|&&count| count >= 2).count()
+    }
+}
+
+/// The colour of cells that no line covers
+const BACKGROUND: [u8; 3] = [255, 255, 255];
+/// The colour of the overlaid lines
+const OVERLAY: [u8; 3] = [0, 0, 0];
+/// The colours that the counts are scaled between, from a single line to the most lines
+const SCALE: [[u8; 3]; 3] = [[49, 54, 149], [254, 224, 144], [165, 0, 38]];
+
+impl Heatmap {
+    /// The most pixels an exported PPM image may have
+    const MAX_PIXELS: usize = 1 << 25;
+
+    /// Marks the lines with the given slopes on a heatmap that is just large enough for them,
+    /// unless it would have more cells than a dense board may have
+    pub fn new(lines: &[Line], slopes: Slopes) -> Option<Heatmap> {
+        let xs = || lines.iter().flat_map(|line| [line.start_x, line.end_x]);
+        let ys = || lines.iter().flat_map(|line| [line.start_y, line.end_y]);
+        let (min_x, max_x) = (xs().min().unwrap_or(0), xs().max().unwrap_or(-1));
+        let (min_y, max_y) = (ys().min().unwrap_or(0), ys().max().unwrap_or(-1));
+
+        let extent = |min: i32, max: i32| {
+            let extent = i64::from(max).checked_sub(min.into())?.checked_add(1)?;
+            usize::try_from(extent).ok()
+        };
+        let (width, height) = (extent(min_x, max_x)?, extent(min_y, max_y)?);
+        if width.checked_mul(height)? > Board::MAX_CELLS {
+            return None;
+        }
+
+        let mut heatmap = Heatmap {
+            origin: (min_x, min_y),
+            counts: Grid::new(width, height),
+        };
+        add_lines(lines, slopes, &mut heatmap);
+        Some(heatmap)
+    }
+
+    fn max_count(&self) -> u32 {
+        self.counts.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Scales the colour of a count between those of [`SCALE`], `max` being the highest count
+    fn colour(count: u32, max: u32) -> [u8; 3] {
+        if count == 0 {
+            return BACKGROUND;
+        }
+
+        let fraction = if max > 1 {
+            f64::from(count - 1) / f64::from(max - 1)
+        } else {
+            0.0
+        };
+        let position = fraction * (SCALE.len() - 1) as f64;
+        let stop = (position as usize).min(SCALE.len() - 2);
+        let weight = position - stop as f64;
+
+        let mut colour = [0; 3];
+        for (channel, value) in colour.iter_mut().enumerate() {
+            let (from, to) = (
+                f64::from(SCALE[stop][channel]),
+                f64::from(SCALE[stop + 1][channel]),
+            );
+            *value = (from + (to - from) * weight).round() as u8;
+        }
+        colour
+    }
+
+    /// The centre of a cell in image coordinates, with `scale` pixels per cell,
+    /// if it can be drawn at all
+    fn centre(&self, x: i32, y: i32, scale: usize) -> Option<(i32, i32)> {
+        let scale = i64::try_from(scale).ok()?;
+        let pixel = |cell: i32, origin: i32| {
+            let offset = (i64::from(cell) - i64::from(origin)).checked_mul(scale)?;
+            i32::try_from(offset.checked_add(scale / 2)?).ok()
+        };
+        let (origin_x, origin_y) = self.origin;
+        Some((pixel(x, origin_x)?, pixel(y, origin_y)?))
+    }
+
+    /// Exports the heatmap as a binary PPM image with `scale` pixels per cell, unless the image
+    /// would have more than [`Heatmap::MAX_PIXELS`] pixels.
+    ///
+    /// The overlaid lines are drawn through the centres of their cells.
+    pub fn to_ppm(&self, scale: usize, overlay: &[Line]) -> Option<Vec<u8>> {
+        let scale = scale.max(1);
+        let width = self.counts.width().checked_mul(scale)?;
+        let height = self.counts.height().checked_mul(scale)?;
+        if width.checked_mul(height)? > Self::MAX_PIXELS {
+            return None;
+        }
+
+        let max = self.max_count();
+        let mut pixels = Grid::from_fn(width, height, |(x, y)| {
+            Heatmap::colour(self.counts[(x / scale, y / scale)], max)
+        });
+
+        for line in overlay {
+            let start = self.centre(line.start_x, line.start_y, scale);
+            let end = self.centre(line.end_x, line.end_y, scale);
+            let (Some((start_x, start_y)), Some((end_x, end_y))) = (start, end) else {
+                continue;
+            };
+            let Some(pixel_line) = Line::new(start_x, start_y, end_x, end_y).clipped(width, height)
+            else {
+                continue;
+            };
+            for (x, y) in pixel_line.points(Rasterisation::Bresenham) {
+                if let Some(pixel) = pixels.get_mut((x as usize, y as usize)) {
+                    *pixel = OVERLAY;
+                }
+            }
+        }
+
+        let mut image = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+        for position in pixels.positions() {
+            image.extend_from_slice(&pixels[position]);
+        }
+        Some(image)
+    }
+
+    /// Exports the heatmap as an SVG image with `scale` pixels per cell, unless its size in
+    /// pixels overflows.
+    ///
+    /// Every covered cell has its count as a tooltip, the overlaid lines are drawn
+    /// through the centres of their cells.
+    pub fn to_svg(&self, scale: usize, overlay: &[Line]) -> Option<String> {
+        let scale = scale.max(1);
+        let max = self.max_count();
+        let (width, height) = self.counts.dim();
+        let (pixels_x, pixels_y) = (width.checked_mul(scale)?, height.checked_mul(scale)?);
+        let (origin_x, origin_y) = self.origin;
+        let colour = |[r, g, b]: [u8; 3]| format!("rgb({},{},{})", r, g, b);
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"{} {} {} {}\">\n",
+            pixels_x,
+            pixels_y,
+            origin_x,
+            origin_y,
+            width,
+            height
+        );
+        svg.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+            origin_x,
+            origin_y,
+            width,
+            height,
+            colour(BACKGROUND)
+        ));
+        for ((x, y), &count) in self.counts.indexed_iter() {
+            if count > 0 {
+                svg.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"1\" height=\"1\" fill=\"{}\"><title>{}</title></rect>\n",
+                    x as i32 + origin_x,
+                    y as i32 + origin_y,
+                    colour(Heatmap::colour(count, max)),
+                    count
+                ));
+            }
+        }
+        for line in overlay {
+            svg.push_str(&format!(
+                "<line x1=\"{}.5\" y1=\"{}.5\" x2=\"{}.5\" y2=\"{}.5\" stroke=\"{}\" stroke-width=\"0.1\"/>\n",
+                line.start_x,
+                line.start_y,
+                line.end_x,
+                line.end_y,
+                colour(OVERLAY)
+            ));
+        }
+        svg.push_str("</svg>\n");
+        Some(svg)
+    }
+}
 
-    for horizontal in &horizontal_lines {
-        board.mark_horizontal(horizontal);
+/// The result of counting overlaps, with the lines that were not drawn
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct OverlapReport {
+    /// The number of points that are covered by at least two of the drawn lines
+    pub overlaps: usize,
+    pub skipped: Vec<Line>,
+}
+
+/// Counts the overlaps of the lines with the given slopes.
+///
+/// Uses a dense board if it is small enough, and a sparse one otherwise.
+pub fn count_overlaps(lines: &[Line], slopes: Slopes) -> OverlapReport {
+    fn count(lines: &[Line], slopes: Slopes, board: &mut impl Overlaps) -> OverlapReport {
+        let skipped = add_lines(lines, slopes, board);
+        OverlapReport {
+            overlaps: board.count_intersections(),
+            skipped,
+        }
     }
-    for vertical_line in &vertical_lines {
-        board.mark_vertical(vertical_line);
+
+    match Board::for_lines(lines) {
+        Some(mut board) => count(lines, slopes, &mut board),
+        None => count(lines, slopes, &mut SparseBoard::default()),
     }
 }
 
-fn add_diagonal_intersections(lines: Vec<Line>, board: &mut Board) {
-    let diags: Vec<_> = lines.into_iter().filter_map(Line::to_diagonal).collect();
+/// The puzzle only asks for the lines that are drawn, the others are left out on purpose
+fn count_straight_overlaps(lines: &[Line]) -> usize {
+    count_overlaps(lines, Slopes::Straight).overlaps
+}
+
+fn count_all_overlaps(lines: &[Line]) -> usize {
+    count_overlaps(lines, Slopes::Diagonal).overlaps
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = Vec<Line>;
+    type Error = InputError;
+
+    const INPUT: &'static str = include_str!("input");
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        parse_lines(input)
+    }
+
+    fn part1(lines: &Self::Input) -> Result<Answer, Self::Error> {
+        Ok(count_straight_overlaps(lines).into())
+    }
+
+    fn part2(lines: &Self::Input) -> Result<Answer, Self::Error> {
+        Ok(count_all_overlaps(lines).into())
+    }
+}
+
+pub fn solve_pt_1() -> usize {
+    solve_pt_1_with_input(Day5::INPUT).expect("shipped input is valid")
+}
+
+pub fn solve_pt_1_with_input(input: &str) -> Result<usize, InputError> {
+    Ok(count_straight_overlaps(&parse_lines(input)?))
+}
+
+pub fn solve_pt_2() -> usize {
+    solve_pt_2_with_input(Day5::INPUT).expect("shipped input is valid")
+}
+
+pub fn solve_pt_2_with_input(input: &str) -> Result<usize, InputError> {
+    Ok(count_all_overlaps(&parse_lines(input)?))
+}
+
+fn parse_lines(input: &str) -> Result<Vec<Line>, InputError> {
+    let lines = aoc::numbered_lines(input)
+        .map(|(line_nr, line)| {
+            parse_line(line).map_err(|error| InputError::InvalidLine {
+                line: line_nr,
+                column: error.location.column,
+                expected: error.expected.to_string(),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
 
-    for diag in &diags {
-        board.mark_diagonal(diag);
+    if lines.is_empty() {
+        return Err(InputError::Empty);
+    }
+    Ok(lines)
+}
+
+/// Marks the lines with the given slopes and returns the ones it skipped
+fn add_lines(lines: &[Line], slopes: Slopes, board: &mut impl Overlaps) -> Vec<Line> {
+    let mut skipped = Vec::new();
+    for line in lines {
+        match slopes.rasterisation(line.kind()) {
+            Some(rasterisation) => board.mark_line(line, rasterisation),
+            None => skipped.push(*line),
+        }
     }
+    skipped
 }
 
 #[cfg(test)]
@@ -215,17 +943,15 @@ mod tests {
         assert_eq!(
             parse_line("55,9 -> 0,9").unwrap(),
             Line {
-                start_x: 0,
+                start_x: 55,
                 start_y: 9,
-                end_x: 55,
+                end_x: 0,
                 end_y: 9
             }
         )
     }
 
-    #[test]
-    fn should_solve_part_one() {
-        let input = "0,9 -> 5,9
+    const EXAMPLE: &str = "0,9 -> 5,9
 8,0 -> 0,8
 9,4 -> 3,4
 2,2 -> 2,1
@@ -235,20 +961,237 @@ mod tests {
 3,4 -> 1,4
 0,0 -> 8,8
 5,5 -> 8,2";
-        let (lines, mut board) = parse(input);
-        add_straight_intersections(lines.clone(), &mut board);
+
+    #[test]
+    fn should_solve_part_one() {
+        let lines = parse_lines(EXAMPLE).unwrap();
+        let mut board = Board::for_lines(&lines).unwrap();
+        let skipped = add_lines(&lines, Slopes::Straight, &mut board);
         let intersections = board.count_intersections();
 
         assert_eq!(intersections, 5);
-        add_diagonal_intersections(lines, &mut board);
+        assert_eq!(skipped.len(), 4);
+        assert!(skipped.iter().all(|line| line.kind() == LineKind::Diagonal));
+        add_lines(&skipped, Slopes::Diagonal, &mut board);
         let intersections = board.count_intersections();
 
         assert_eq!(intersections, 12);
     }
 
+    #[test]
+    fn should_count_the_same_on_sparse_boards() {
+        let lines = parse_lines(Day5::INPUT).unwrap();
+        for slopes in [Slopes::Straight, Slopes::Diagonal] {
+            let mut dense = Board::for_lines(&lines).unwrap();
+            let mut sparse = SparseBoard::default();
+            add_lines(&lines, slopes, &mut dense);
+            add_lines(&lines, slopes, &mut sparse);
+            assert_eq!(sparse.count_intersections(), dense.count_intersections());
+        }
+    }
+
+    #[test]
+    fn should_rasterise_any_slope() {
+        let line = Line::new(0, 0, 4, 2);
+        assert_eq!(line.kind(), LineKind::Sloped);
+        assert_eq!(
+            line.points(Rasterisation::LatticePoints)
+                .collect::<Vec<_>>(),
+            vec![(0, 0), (2, 1), (4, 2)]
+        );
+        assert_eq!(
+            line.points(Rasterisation::Bresenham).collect::<Vec<_>>(),
+            vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)]
+        );
+
+        let diagonal = Line::new(3, 1, 0, 4);
+        assert_eq!(diagonal.kind(), LineKind::Diagonal);
+        assert!(diagonal
+            .points(Rasterisation::Bresenham)
+            .eq(diagonal.points(Rasterisation::LatticePoints)));
+        assert_eq!(
+            Line::new(2, 2, 2, 2)
+                .points(Rasterisation::Bresenham)
+                .collect::<Vec<_>>(),
+            vec![(2, 2)]
+        );
+
+        let lines = parse_lines("0,0 -> 4,2\n4,0 -> 0,2\n0,1 -> 4,1").unwrap();
+        let report = count_overlaps(&lines, Slopes::Diagonal);
+        assert_eq!(report.overlaps, 0);
+        assert_eq!(report.skipped, lines[..2].to_vec());
+
+        let lattice = count_overlaps(&lines, Slopes::Any(Rasterisation::LatticePoints));
+        assert_eq!(lattice.overlaps, 1);
+        assert!(lattice.skipped.is_empty());
+        let bresenham = count_overlaps(&lines, Slopes::Any(Rasterisation::Bresenham));
+        assert_eq!(bresenham.overlaps, 3);
+    }
+
+    #[test]
+    fn should_handle_negative_and_large_coordinates() {
+        let lines = parse_lines("-5,-5 -> 5,5\n-5,5 -> 5,-5\n0,-3 -> 0,3\n-2,1 -> 2,1").unwrap();
+        assert!(Board::for_lines(&lines).is_none());
+        assert_eq!(count_straight_overlaps(&lines), 1);
+        assert_eq!(count_all_overlaps(&lines), 4);
+
+        let lines = parse_lines(
+            "100000,0 -> 100000,200000\n0,100000 -> 200000,100000\n0,0 -> 100000,100000",
+        )
+        .unwrap();
+        assert!(Board::for_lines(&lines).is_none());
+        assert_eq!(count_straight_overlaps(&lines), 1);
+        assert_eq!(count_all_overlaps(&lines), 1);
+    }
+
+    #[test]
+    fn should_count_long_lines_without_walking_them() {
+        let lines = parse_lines(
+            "0,0 -> 2000000000,0\n2000000000,0 -> 0,0\n5,-5 -> 5,5\n-5,-5 -> 5,5\n3,1 -> 7,3",
+        )
+        .unwrap();
+        assert_eq!(count_straight_overlaps(&lines), 2000000001);
+        assert_eq!(count_all_overlaps(&lines), 2000000002);
+        assert_eq!(
+            count_overlaps(&lines, Slopes::Any(Rasterisation::LatticePoints)).overlaps,
+            2000000003
+        );
+    }
+
+    #[test]
+    fn should_intersect_lines() {
+        let point = |x, y, denominator| Some(Intersection::Point(ExactPoint { x, y, denominator }));
+        let diagonal = Line::new(0, 0, 4, 4);
+
+        assert_eq!(
+            diagonal.intersection(&Line::new(0, 4, 4, 0)),
+            point(2, 2, 1)
+        );
+        assert_eq!(
+            diagonal.intersection(&Line::new(0, 1, 1, 0)),
+            point(1, 1, 2)
+        );
+        assert_eq!(
+            diagonal.intersection(&Line::new(4, 4, 9, 0)),
+            point(4, 4, 1)
+        );
+        assert_eq!(diagonal.intersection(&Line::new(3, 0, 4, 1)), None);
+        assert_eq!(diagonal.intersection(&Line::new(5, 5, 7, 7)), None);
+        assert_eq!(
+            diagonal.intersection(&Line::new(6, 6, 2, 2)),
+            Some(Intersection::Segment(Line::new(2, 2, 4, 4)))
+        );
+        assert_eq!(
+            diagonal.intersection(&Line::new(3, 3, 3, 3)),
+            point(3, 3, 1)
+        );
+        assert_eq!(Line::new(1, 3, 1, 3).intersection(&diagonal), None);
+    }
+
+    #[test]
+    fn should_list_contributing_lines() {
+        let lines = parse_lines(EXAMPLE).unwrap();
+        let cells = contributing_lines(&lines);
+
+        assert_eq!(cells.len(), count_all_overlaps(&lines));
+        assert_eq!(cells[&(2, 2)], vec![3, 8]);
+        assert_eq!(cells[&(0, 9)], vec![0, 6]);
+
+        let mut board = Board::for_lines(&lines).unwrap();
+        add_lines(&lines, Slopes::Diagonal, &mut board);
+        for (&(x, y), contributors) in &cells {
+            assert_eq!(
+                board.0[(x as usize, y as usize)] as usize,
+                contributors.len()
+            );
+        }
+    }
+
+    #[test]
+    fn should_export_heatmaps() {
+        let lines = parse_lines("0,0 -> 2,0\n1,0 -> 1,1").unwrap();
+        let heatmap = Heatmap::new(&lines, Slopes::Straight).unwrap();
+        assert_eq!(heatmap.count_intersections(), 1);
+
+        let ppm = heatmap.to_ppm(1, &[]).unwrap();
+        let header = b"P6\n3 2\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        let pixels: Vec<_> = ppm[header.len()..].chunks(3).collect();
+        assert_eq!(
+            pixels,
+            vec![
+                &SCALE[0],
+                &SCALE[2],
+                &SCALE[0],
+                &BACKGROUND,
+                &SCALE[0],
+                &BACKGROUND
+            ]
+        );
+
+        let overlaid = heatmap.to_ppm(3, &lines[..1]).unwrap();
+        assert_eq!(overlaid.len(), "P6\n9 6\n255\n".len() + 9 * 6 * 3);
+        assert_eq!(&overlaid[overlaid.len() - 3..], &BACKGROUND);
+        // the line runs through the centres of the cells, so it starts at pixel (1, 1)
+        let centre = "P6\n9 6\n255\n".len() + (9 + 1) * 3;
+        assert_eq!(&overlaid[centre - 3..centre], &SCALE[0]);
+        assert_eq!(&overlaid[centre..centre + 3], &OVERLAY);
+
+        let heatmap = Heatmap::new(&[Line::new(-1, -1, 1, 1)], Slopes::Diagonal).unwrap();
+        let svg = heatmap.to_svg(10, &[]).unwrap();
+        assert!(svg.contains("width=\"30\" height=\"30\" viewBox=\"-1 -1 3 3\""));
+        assert_eq!(svg.matches("<title>1</title>").count(), 3);
+        assert!(!svg.contains("<line"));
+        assert_eq!(heatmap.to_svg(0, &[]), heatmap.to_svg(1, &[]));
+        assert_eq!(heatmap.to_ppm(0, &[]), heatmap.to_ppm(1, &[]));
+
+        let far_apart = Line::new(-2_000_000_000, 0, 2_000_000_000, 0);
+        assert!(Heatmap::new(&[far_apart], Slopes::Straight).is_none());
+        let overlaid = heatmap.to_ppm(2, &[far_apart]);
+        assert_eq!(overlaid, heatmap.to_ppm(2, &[]));
+
+        let heatmap = Heatmap::new(&lines, Slopes::Straight).unwrap();
+        let long = Line::new(0, 0, 50_000_000, 0);
+        let overlaid = heatmap.to_ppm(1, &[long]).unwrap();
+        let pixels: Vec<_> = overlaid[header.len()..].chunks(3).collect();
+        assert_eq!(pixels[..3], [&OVERLAY; 3]);
+        assert_eq!(
+            pixels[3..],
+            ppm[header.len() + 9..].chunks(3).collect::<Vec<_>>()
+        );
+
+        assert_eq!(heatmap.to_ppm(usize::MAX / 2, &[]), None);
+        assert_eq!(heatmap.to_ppm(4096, &[]), None);
+        assert_eq!(heatmap.to_svg(usize::MAX / 2, &[]), None);
+    }
+
+    #[test]
+    fn should_report_malformed_lines() {
+        assert_eq!(
+            parse_lines("0,9 -> 5,9\n8,0 => 0,8"),
+            Err(InputError::InvalidLine {
+                line: 2,
+                column: 4,
+                expected: "one of \" -> \", ['0'..='9']".to_string()
+            })
+        );
+        assert_eq!(
+            parse_lines("0,9 -> 5,99999999999"),
+            Err(InputError::InvalidLine {
+                line: 1,
+                column: 21,
+                expected: "one of ['0'..='9'], a number that fits into an i32".to_string()
+            })
+        );
+        assert_eq!(parse_lines(""), Err(InputError::Empty));
+    }
+
     #[test]
     fn print_day_5() {
         println!("The number of overlaps is {}", solve_pt_1());
-        println!("The total number of overlaps with diagonals is {}", solve_pt_2());
+        println!(
+            "The total number of overlaps with diagonals is {}",
+            solve_pt_2()
+        );
     }
 }
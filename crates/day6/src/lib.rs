@@ -1,42 +1,132 @@
+use aoc::{Answer, Solution};
+use num_bigint::BigUint;
+use thiserror::Error;
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum InputError {
+    #[error("line {line}, column {column}: '{value}' is not a timer between 0 and {max}")]
+    InvalidTimer {
+        line: usize,
+        column: usize,
+        value: String,
+        max: usize,
+    },
+    #[error("fishes have to spawn at least once, a lifetime of 0 cycles is not allowed")]
+    NoLifetime,
+}
+
+/// How a species of lanternfish reproduces
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SpawnRules {
+    /// The timer of a fish right after it spawned
+    pub reset_timer: usize,
+    /// The timer of a newborn fish
+    pub newborn_timer: usize,
+    /// How many fishes are born at once
+    pub offspring: usize,
+    /// How often a fish spawns before it dies, fishes live forever if `None`, `Some(0)` is
+    /// rejected when parsing
+    pub lifetime_cycles: Option<usize>,
+}
+
+impl Default for SpawnRules {
+    /// The rules of the lanternfish in the puzzle
+    fn default() -> Self {
+        SpawnRules {
+            reset_timer: 6,
+            newborn_timer: 8,
+            offspring: 1,
+            lifetime_cycles: None,
+        }
+    }
+}
+
+impl SpawnRules {
+    /// The highest timer a fish can have
+    fn max_timer(&self) -> usize {
+        self.reset_timer.max(self.newborn_timer)
+    }
+
+    /// How many spawn cycles are told apart, mortal fishes need one bucket per cycle they live
+    fn cycles(&self) -> usize {
+        self.lifetime_cycles.unwrap_or(1)
+    }
+
+    fn bucket_count(&self) -> usize {
+        (self.max_timer() + 1) * self.cycles()
+    }
+
+    fn bucket(&self, time_until_spawn: usize, cycles_spawned: usize) -> usize {
+        cycles_spawned * (self.max_timer() + 1) + time_until_spawn
+    }
+
+    fn empty_buckets(&self) -> Vec<LanternFishData> {
+        (0..self.cycles())
+            .flat_map(|cycles_spawned| {
+                (0..=self.max_timer()).map(move |time_until_spawn| {
+                    LanternFishData::new(time_until_spawn, cycles_spawned, 0)
+                })
+            })
+            .collect()
+    }
+
+    /// The buckets the fishes of a bucket end up in the next day, and how many fishes each
+    /// of them becomes there
+    fn successors(&self, fish: &LanternFishData) -> Vec<(usize, usize)> {
+        if fish.time_until_spawn > 0 {
+            return vec![(
+                self.bucket(fish.time_until_spawn - 1, fish.cycles_spawned),
+                1,
+            )];
+        }
+
+        let mut successors = vec![(self.bucket(self.newborn_timer, 0), self.offspring)];
+        let cycles_spawned = fish.cycles_spawned + 1;
+        match self.lifetime_cycles {
+            None => successors.push((self.bucket(self.reset_timer, 0), 1)),
+            Some(lifetime) if cycles_spawned < lifetime => {
+                successors.push((self.bucket(self.reset_timer, cycles_spawned), 1))
+            }
+            Some(_) => {}
+        }
+        successors
+    }
+}
+
+#[derive(Clone)]
 struct LanternFishData {
     time_until_spawn: usize,
+    cycles_spawned: usize,
     nr_in_bucket: usize,
 }
 
 impl LanternFishData {
-    fn new(time_until_spawn: usize, nr_in_bucket: usize) -> Self {
+    fn new(time_until_spawn: usize, cycles_spawned: usize, nr_in_bucket: usize) -> Self {
         LanternFishData {
             time_until_spawn,
+            cycles_spawned,
             nr_in_bucket,
         }
     }
 }
-struct LanternFishes(Vec<LanternFishData>);
+
+#[derive(Clone)]
+pub struct LanternFishes {
+    rules: SpawnRules,
+    fishes: Vec<LanternFishData>,
+}
 
 impl LanternFishes {
     fn tick(mut self) -> Self {
-        let mut new_fish_data = vec![
-            LanternFishData::new(0, 0),
-            LanternFishData::new(1, 0),
-            LanternFishData::new(2, 0),
-            LanternFishData::new(3, 0),
-            LanternFishData::new(4, 0),
-            LanternFishData::new(5, 0),
-            LanternFishData::new(6, 0),
-            LanternFishData::new(7, 0),
-            LanternFishData::new(8, 0),
-        ];
-
-        self.0.into_iter().for_each(|fish| {
-            if fish.time_until_spawn == 0 {
-                new_fish_data[6].nr_in_bucket += fish.nr_in_bucket;
-                new_fish_data[8].nr_in_bucket += fish.nr_in_bucket;
-            } else {
-                new_fish_data[fish.time_until_spawn - 1].nr_in_bucket += fish.nr_in_bucket;
+        let mut new_fish_data = self.rules.empty_buckets();
+
+        self.fishes.iter().for_each(|fish| {
+            for (bucket, multiplier) in self.rules.successors(fish) {
+                new_fish_data[bucket].nr_in_bucket += fish.nr_in_bucket * multiplier;
             }
         });
 
-        self.0 = new_fish_data;
+        self.fishes = new_fish_data;
         self
     }
 
@@ -48,41 +138,141 @@ impl LanternFishes {
     }
 
     fn population(&self) -> usize {
-        self.0.iter().map(|f| f.nr_in_bucket).sum()
-    }
-}
-
-fn parse_inputs(input: &str) -> LanternFishes {
-    let mut fishes = vec![
-        LanternFishData::new(0, 0),
-        LanternFishData::new(1, 0),
-        LanternFishData::new(2, 0),
-        LanternFishData::new(3, 0),
-        LanternFishData::new(4, 0),
-        LanternFishData::new(5, 0),
-        LanternFishData::new(6, 0),
-        LanternFishData::new(7, 0),
-        LanternFishData::new(8, 0),
-    ];
-    input
-        .trim()
-        .split(",")
-        .map(|line| line.parse::<usize>().expect("input should be ints"))
-        .for_each(|time_to_spawn| {
-            let data = fishes.get_mut(time_to_spawn).expect("just constructed");
+        self.fishes.iter().map(|f| f.nr_in_bucket).sum()
+    }
+
+    /// How the fishes of every bucket end up in the buckets of the next day
+    fn daily_transition(&self) -> Matrix {
+        let mut transition = Matrix::zero(self.rules.bucket_count());
+        for (source, fish) in self.fishes.iter().enumerate() {
+            for (target, multiplier) in self.rules.successors(fish) {
+                transition.0[target][source] += multiplier;
+            }
+        }
+        transition
+    }
+
+    /// The exact population after any number of days.
+    ///
+    /// The daily transition is squared once per bit of `days`, so this takes logarithmic time.
+    pub fn population_after(&self, days: u64) -> BigUint {
+        let mut buckets: Vec<BigUint> = self.fishes.iter().map(|f| f.nr_in_bucket.into()).collect();
+        let mut transition = self.daily_transition();
+        let mut days = days;
+
+        while days > 0 {
+            if days & 1 == 1 {
+                buckets = transition.apply(&buckets);
+            }
+            days >>= 1;
+            if days > 0 {
+                transition = transition.mul(&transition);
+            }
+        }
+        buckets.into_iter().sum()
+    }
+}
+
+/// A square matrix of arbitrary-precision counts
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Matrix(Vec<Vec<BigUint>>);
+
+impl Matrix {
+    fn zero(size: usize) -> Self {
+        Matrix(vec![vec![BigUint::default(); size]; size])
+    }
+
+    fn mul(&self, other: &Matrix) -> Matrix {
+        let size = self.0.len();
+        let mut product = Matrix::zero(size);
+        for (row, product_row) in product.0.iter_mut().enumerate() {
+            for (column, cell) in product_row.iter_mut().enumerate() {
+                *cell = (0..size)
+                    .map(|k| &self.0[row][k] * &other.0[k][column])
+                    .sum();
+            }
+        }
+        product
+    }
+
+    fn apply(&self, vector: &[BigUint]) -> Vec<BigUint> {
+        self.0
+            .iter()
+            .map(|row| row.iter().zip(vector).map(|(a, b)| a * b).sum())
+            .collect()
+    }
+}
+
+fn parse_inputs(input: &str) -> Result<LanternFishes, InputError> {
+    parse_inputs_with_rules(input, SpawnRules::default())
+}
+
+/// Parses the timers of a school of fishes that reproduce by the given rules.
+///
+/// All fishes are taken to be in their first spawn cycle.
+pub fn parse_inputs_with_rules(
+    input: &str,
+    rules: SpawnRules,
+) -> Result<LanternFishes, InputError> {
+    if rules.lifetime_cycles == Some(0) {
+        return Err(InputError::NoLifetime);
+    }
+
+    let mut fishes = rules.empty_buckets();
+    for (line_nr, line) in aoc::numbered_lines(input) {
+        for value in line.split(',').map(str::trim) {
+            let data = value
+                .parse::<usize>()
+                .ok()
+                .filter(|&time_to_spawn| time_to_spawn <= rules.max_timer())
+                .map(|time_to_spawn| &mut fishes[rules.bucket(time_to_spawn, 0)])
+                .ok_or_else(|| InputError::InvalidTimer {
+                    line: line_nr,
+                    column: aoc::column(line, value),
+                    value: value.to_string(),
+                    max: rules.max_timer(),
+                })?;
             data.nr_in_bucket += 1;
-        });
-    LanternFishes(fishes)
+        }
+    }
+    Ok(LanternFishes { rules, fishes })
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = LanternFishes;
+    type Error = InputError;
+
+    const INPUT: &'static str = include_str!("input");
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        parse_inputs(input)
+    }
+
+    fn part1(fishes: &Self::Input) -> Result<Answer, Self::Error> {
+        Ok(fishes.clone().tick_n(80).population().into())
+    }
+
+    fn part2(fishes: &Self::Input) -> Result<Answer, Self::Error> {
+        Ok(fishes.clone().tick_n(256).population().into())
+    }
 }
 
-fn solve_pt_1() -> usize {
-    let input = include_str!("input");
-    parse_inputs(input).tick_n(80).population()
+pub fn solve_pt_1() -> usize {
+    solve_pt_1_with_input(Day6::INPUT).expect("shipped input is valid")
 }
 
-fn solve_pt_2() -> usize {
-    let input = include_str!("input");
-    parse_inputs(input).tick_n(256).population()
+pub fn solve_pt_1_with_input(input: &str) -> Result<usize, InputError> {
+    Ok(parse_inputs(input)?.tick_n(80).population())
+}
+
+pub fn solve_pt_2() -> usize {
+    solve_pt_2_with_input(Day6::INPUT).expect("shipped input is valid")
+}
+
+pub fn solve_pt_2_with_input(input: &str) -> Result<usize, InputError> {
+    Ok(parse_inputs(input)?.tick_n(256).population())
 }
 
 #[cfg(test)]
@@ -91,11 +281,109 @@ mod tests {
 
     #[test]
     fn should_produce_test_output() {
-        let population = parse_inputs("3,4,3,1,2").tick_n(80).population();
+        let population = parse_inputs("3,4,3,1,2").unwrap().tick_n(80).population();
 
         assert_eq!(population, 5934)
     }
 
+    #[test]
+    fn should_jump_to_any_day() {
+        let fishes = parse_inputs("3,4,3,1,2").unwrap();
+
+        assert_eq!(fishes.population_after(0), 5u32.into());
+        assert_eq!(fishes.population_after(18), 26u32.into());
+        assert_eq!(fishes.population_after(256), 26984457539u64.into());
+
+        // step day by day with exact counts, far beyond where usize overflows
+        let mut buckets: Vec<BigUint> = fishes
+            .fishes
+            .iter()
+            .map(|f| f.nr_in_bucket.into())
+            .collect();
+        for _ in 0..1000 {
+            buckets.rotate_left(1);
+            let newborns = buckets[8].clone();
+            buckets[6] += newborns;
+        }
+        let population: BigUint = buckets.into_iter().sum();
+        assert_eq!(fishes.population_after(1000), population);
+        assert!(population > BigUint::from(u128::MAX));
+    }
+
+    #[test]
+    fn should_follow_spawn_rules() {
+        let rules = SpawnRules {
+            reset_timer: 2,
+            newborn_timer: 3,
+            offspring: 2,
+            lifetime_cycles: Some(2),
+        };
+        let fishes = parse_inputs_with_rules("3,0", rules).unwrap();
+
+        // on day 4 both old fishes spawn twins, but the second one dies after its second spawn
+        let populations: Vec<_> = (0..=4)
+            .map(|day| fishes.clone().tick_n(day).population())
+            .collect();
+        assert_eq!(populations, vec![2, 4, 4, 4, 7]);
+        for day in 0..=20 {
+            assert_eq!(
+                fishes.population_after(day as u64),
+                fishes.clone().tick_n(day).population().into()
+            );
+        }
+
+        let default = parse_inputs_with_rules("3,4,3,1,2", SpawnRules::default()).unwrap();
+        assert_eq!(default.tick_n(80).population(), 5934);
+        assert_eq!(
+            parse_inputs_with_rules("3,4", rules_with_short_timers()).err(),
+            Some(InputError::InvalidTimer {
+                line: 1,
+                column: 3,
+                value: "4".to_string(),
+                max: 3
+            })
+        );
+
+        let no_lifetime = SpawnRules {
+            lifetime_cycles: Some(0),
+            ..SpawnRules::default()
+        };
+        assert_eq!(
+            parse_inputs_with_rules("3", no_lifetime).err(),
+            Some(InputError::NoLifetime)
+        );
+    }
+
+    fn rules_with_short_timers() -> SpawnRules {
+        SpawnRules {
+            reset_timer: 1,
+            newborn_timer: 3,
+            ..SpawnRules::default()
+        }
+    }
+
+    #[test]
+    fn should_report_invalid_timers() {
+        assert_eq!(
+            parse_inputs("3,4,9,1").err(),
+            Some(InputError::InvalidTimer {
+                line: 1,
+                column: 5,
+                value: "9".to_string(),
+                max: 8
+            })
+        );
+        assert_eq!(
+            parse_inputs("3,4\n1, x").err(),
+            Some(InputError::InvalidTimer {
+                line: 2,
+                column: 4,
+                value: "x".to_string(),
+                max: 8
+            })
+        );
+    }
+
     #[test]
     fn print_day_6() {
         println!("After 80 days, there are {} Lanternfish", solve_pt_1());
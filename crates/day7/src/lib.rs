@@ -1,37 +1,155 @@
+use aoc::{Answer, Solution};
+use thiserror::Error;
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum InputError {
+    #[error("line {line}, column {column}: '{value}' is not a position")]
+    InvalidPosition {
+        line: usize,
+        column: usize,
+        value: String,
+    },
+    #[error("the input has no crabs")]
+    Empty,
+}
+
 #[derive(Clone)]
-struct CrabPosition(i32);
+pub struct CrabPosition(i32);
 
 impl CrabPosition {
-    fn fuel_to_position_correct_consumption(&self, pos: i32) -> i32 {
-        let dist = (self.0 - pos).abs();
-        dist*(dist+1)/2
+    fn distance(&self, pos: i32) -> i64 {
+        (i64::from(self.0) - i64::from(pos)).abs()
+    }
+}
+
+impl From<i32> for CrabPosition {
+    fn from(position: i32) -> Self {
+        CrabPosition(position)
+    }
+}
+
+/// How much fuel a crab engine burns to move a distance
+pub trait FuelCost {
+    fn fuel(&self, distance: i64) -> i64;
+}
+
+/// Every step costs one fuel
+pub struct Linear;
+
+impl FuelCost for Linear {
+    fn fuel(&self, distance: i64) -> i64 {
+        distance
+    }
+}
+
+/// Every step costs one fuel more than the one before
+pub struct Triangular;
+
+impl FuelCost for Triangular {
+    fn fuel(&self, distance: i64) -> i64 {
+        distance * (distance + 1) / 2
+    }
+}
+
+/// The fuel grows with the square of the distance
+pub struct Quadratic;
+
+impl FuelCost for Quadratic {
+    fn fuel(&self, distance: i64) -> i64 {
+        distance * distance
+    }
+}
+
+/// A cost model given as a closure, e.g. `Custom(|distance| 3 * distance)`
+pub struct Custom<F>(pub F);
+
+impl<F: Fn(i64) -> i64> FuelCost for Custom<F> {
+    fn fuel(&self, distance: i64) -> i64 {
+        (self.0)(distance)
     }
 }
 
-fn parse_input(input: &str) -> Vec<CrabPosition> {
-    input
-        .trim()
-        .split(",")
-        .map(|line| CrabPosition(line.parse::<i32>().expect("input should be ints")))
-        .collect()
+/// The fuel all crabs need to move to the position
+pub fn total_fuel(crabs: &[CrabPosition], pos: i32, cost: &impl FuelCost) -> i64 {
+    crabs.iter().map(|crab| cost.fuel(crab.distance(pos))).sum()
+}
+
+/// Finds the leftmost position that needs the least fuel, together with that fuel.
+///
+/// The cost has to grow with the distance without ever growing slower, so that the total
+/// fuel is convex and a binary search for the point where it stops falling finds the optimum.
+pub fn optimal_position(crabs: &[CrabPosition], cost: &impl FuelCost) -> Option<(i32, i64)> {
+    let mut low = crabs.iter().map(|crab| crab.0).min()?;
+    let mut high = crabs.iter().map(|crab| crab.0).max()?;
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if total_fuel(crabs, mid, cost) <= total_fuel(crabs, mid + 1, cost) {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    Some((low, total_fuel(crabs, low, cost)))
 }
 
-fn get_optimal_position_with_old_fuel_consumption(crabs: &Vec<CrabPosition>) -> i32 {
-    let mut x1 = crabs.clone();
+fn parse_input(input: &str) -> Result<Vec<CrabPosition>, InputError> {
+    let crabs = aoc::numbered_lines(input)
+        .flat_map(|(line_nr, line)| {
+            line.split(',').map(str::trim).map(move |value| {
+                value
+                    .parse::<i32>()
+                    .map(CrabPosition)
+                    .map_err(|_| InputError::InvalidPosition {
+                        line: line_nr,
+                        column: aoc::column(line, value),
+                        value: value.to_string(),
+                    })
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if crabs.is_empty() {
+        return Err(InputError::Empty);
+    }
+    Ok(crabs)
+}
+
+fn get_optimal_position_with_old_fuel_consumption(crabs: &[CrabPosition]) -> i32 {
+    let mut x1 = crabs.to_vec();
     x1.sort_by_key(|c| c.0);
 
     let mid = x1.len() / 2;
-    if x1.len() % 2 == 0 {
+    if x1.len().is_multiple_of(2) {
         (x1[mid - 1].0 + x1[mid].0)/2
     } else {
         x1[mid].0
     }
 }
 
-fn get_optimal_position_with_correct_fuel_consumption(crabs: &Vec<CrabPosition>) -> (i32, i32) {
-    let mut optimal_position = 0;
-    let mut optimal_fuel_consumption = i32::MAX;
-    for possible_position in 0..crabs.iter().max_by_key(|c|c.0).expect("not empty").0 {
+/// Jumps to the optimal position for the correct fuel consumption.
+///
+/// The total fuel is convex and its minimum lies within half a step of the mean position,
+/// so only the positions around the mean need to be compared.
+fn get_optimal_position_with_correct_fuel_consumption(crabs: &[CrabPosition]) -> (i32, i64) {
+    let sum: i64 = crabs.iter().map(|crab| i64::from(crab.0)).sum();
+    let mean = sum.div_euclid(crabs.len() as i64) as i32;
+
+    (mean - 1..=mean + 1)
+        .map(|position| (position, fuel_to_position_correct_consumption(crabs, position)))
+        .min_by_key(|&(position, fuel)| (fuel, position))
+        .expect("not empty")
+}
+
+/// Tries every position between the outermost crabs, the reference for the closed form
+#[cfg(test)]
+fn search_optimal_position_with_correct_fuel_consumption(crabs: &[CrabPosition]) -> (i32, i64) {
+    let min = crabs.iter().map(|c| c.0).min().expect("not empty");
+    let max = crabs.iter().map(|c| c.0).max().expect("not empty");
+
+    let mut optimal_position = min;
+    let mut optimal_fuel_consumption = i64::MAX;
+    for possible_position in min..=max {
         let new_fuel = fuel_to_position_correct_consumption(crabs, possible_position);
         if new_fuel < optimal_fuel_consumption {
             optimal_position = possible_position;
@@ -42,26 +160,56 @@ fn get_optimal_position_with_correct_fuel_consumption(crabs: &Vec<CrabPosition>)
     (optimal_position, optimal_fuel_consumption)
 }
 
-fn fuel_to_position_correct_consumption(crabs: &Vec<CrabPosition>, pos: i32) -> i32 {
-    crabs.iter().map(|crab| crab.fuel_to_position_correct_consumption(pos)).sum()
+fn fuel_to_position_correct_consumption(crabs: &[CrabPosition], pos: i32) -> i64 {
+    total_fuel(crabs, pos, &Triangular)
 }
 
 
-fn fuel_to_position_old_consumption(crabs: &Vec<CrabPosition>, pos: i32) -> i32 {
-    crabs.iter().map(|crab| (crab.0 - pos).abs()).sum()
+fn fuel_to_position_old_consumption(crabs: &[CrabPosition], pos: i32) -> i64 {
+    total_fuel(crabs, pos, &Linear)
 }
 
-fn solve_pt_1() -> i32 {
-    let input = include_str!("input");
-    let crabs = parse_input(input);
-    fuel_to_position_old_consumption(&crabs, get_optimal_position_with_old_fuel_consumption(&crabs))
+fn fuel_with_old_consumption(crabs: &[CrabPosition]) -> i64 {
+    fuel_to_position_old_consumption(crabs, get_optimal_position_with_old_fuel_consumption(crabs))
 }
 
-fn solve_pt_2() -> i32 {
-    let input = include_str!("input");
+pub struct Day7;
 
-    let crabs = parse_input(input);
-     get_optimal_position_with_correct_fuel_consumption(&crabs).1
+impl Solution for Day7 {
+    type Input = Vec<CrabPosition>;
+    type Error = InputError;
+
+    const INPUT: &'static str = include_str!("input");
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        parse_input(input)
+    }
+
+    fn part1(crabs: &Self::Input) -> Result<Answer, Self::Error> {
+        Ok(fuel_with_old_consumption(crabs).into())
+    }
+
+    fn part2(crabs: &Self::Input) -> Result<Answer, Self::Error> {
+        Ok(get_optimal_position_with_correct_fuel_consumption(crabs).1.into())
+    }
+}
+
+pub fn solve_pt_1() -> i64 {
+    solve_pt_1_with_input(Day7::INPUT).expect("shipped input is valid")
+}
+
+pub fn solve_pt_1_with_input(input: &str) -> Result<i64, InputError> {
+    let crabs = parse_input(input)?;
+    Ok(fuel_with_old_consumption(&crabs))
+}
+
+pub fn solve_pt_2() -> i64 {
+    solve_pt_2_with_input(Day7::INPUT).expect("shipped input is valid")
+}
+
+pub fn solve_pt_2_with_input(input: &str) -> Result<i64, InputError> {
+    let crabs = parse_input(input)?;
+    Ok(get_optimal_position_with_correct_fuel_consumption(&crabs).1)
 }
 
 #[cfg(test)]
@@ -71,17 +219,100 @@ mod tests {
     #[test]
     fn should_find_correct_input() {
         let input = "16,1,2,0,4,2,7,1,2,14";
+        let crabs = parse_input(input).unwrap();
 
-        assert_eq!(get_optimal_position_with_old_fuel_consumption(&parse_input(input)), 2);
-        assert_eq!(fuel_to_position_old_consumption(&parse_input(input), 2), 37);
-        assert_eq!(get_optimal_position_with_correct_fuel_consumption(&parse_input(input)), (5,168));
+        assert_eq!(get_optimal_position_with_old_fuel_consumption(&crabs), 2);
+        assert_eq!(fuel_to_position_old_consumption(&crabs, 2), 37);
+        assert_eq!(get_optimal_position_with_correct_fuel_consumption(&crabs), (5,168));
+        assert_eq!(search_optimal_position_with_correct_fuel_consumption(&crabs), (5,168));
+    }
+
+    /// Crabs spread pseudo-randomly over `0..spread`
+    fn scattered_crabs(count: usize, spread: u64, seed: u64) -> Vec<CrabPosition> {
+        let mut state = seed;
+        (0..count)
+            .map(|_| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                CrabPosition(((state >> 33) % spread) as i32)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_match_the_brute_force() {
+        for seed in 0..50 {
+            let crabs = scattered_crabs(1 + seed as usize % 20, 100, seed);
+            let median = get_optimal_position_with_old_fuel_consumption(&crabs);
+            let min = crabs.iter().map(|c| c.0).min().unwrap();
+            let max = crabs.iter().map(|c| c.0).max().unwrap();
+            let least_fuel = (min..=max)
+                .map(|position| fuel_to_position_old_consumption(&crabs, position))
+                .min()
+                .unwrap();
+
+            assert_eq!(fuel_to_position_old_consumption(&crabs, median), least_fuel);
+            assert_eq!(
+                get_optimal_position_with_correct_fuel_consumption(&crabs),
+                search_optimal_position_with_correct_fuel_consumption(&crabs)
+            );
+        }
+
+        // the outermost crab can be the optimal position, and crabs can be left of zero
+        let crabs = vec![CrabPosition(5), CrabPosition(5)];
+        assert_eq!(search_optimal_position_with_correct_fuel_consumption(&crabs), (5, 0));
+        let crabs = vec![CrabPosition(-7), CrabPosition(-3), CrabPosition(-3)];
+        assert_eq!(search_optimal_position_with_correct_fuel_consumption(&crabs), (-4, 8));
+        assert_eq!(get_optimal_position_with_correct_fuel_consumption(&crabs), (-4, 8));
+    }
+
+    #[test]
+    fn should_optimise_any_convex_cost() {
+        let crabs = parse_input("16,1,2,0,4,2,7,1,2,14").unwrap();
+
+        assert_eq!(optimal_position(&crabs, &Linear), Some((2, 37)));
+        assert_eq!(optimal_position(&crabs, &Triangular), Some((5, 168)));
+        assert_eq!(optimal_position(&crabs, &Quadratic), Some((5, 291)));
+        assert_eq!(optimal_position(&crabs, &Custom(|d| d * d * d)), Some((6, 2179)));
+        assert_eq!(optimal_position(&[], &Linear), None);
+
+        for seed in 0..20 {
+            let crabs = scattered_crabs(1 + seed as usize, 50, seed);
+            let cost = Custom(|distance| 2 * distance * distance + distance);
+            let least_fuel = (0..50).map(|pos| total_fuel(&crabs, pos, &cost)).min();
+
+            assert_eq!(optimal_position(&crabs, &cost).map(|(_, fuel)| fuel), least_fuel);
+        }
+    }
+
+    #[test]
+    fn should_align_crabs_spread_over_millions() {
+        let crabs = scattered_crabs(1000, 5_000_000, 7);
+        let (position, fuel) = get_optimal_position_with_correct_fuel_consumption(&crabs);
+
+        assert_eq!(fuel, fuel_to_position_correct_consumption(&crabs, position));
+        assert!(fuel <= fuel_to_position_correct_consumption(&crabs, position - 1));
+        assert!(fuel <= fuel_to_position_correct_consumption(&crabs, position + 1));
+        assert!(fuel > i64::from(i32::MAX));
     }
 
     #[test]
     fn should_determine_fuel_costs() {
-        assert_eq!(CrabPosition(5).fuel_to_position_correct_consumption(6), 1);
-        assert_eq!(CrabPosition(5).fuel_to_position_correct_consumption(3), 3);
-        assert_eq!(CrabPosition(5).fuel_to_position_correct_consumption(8), 6);
+        assert_eq!(Triangular.fuel(CrabPosition(5).distance(6)), 1);
+        assert_eq!(Triangular.fuel(CrabPosition(5).distance(3)), 3);
+        assert_eq!(Triangular.fuel(CrabPosition(5).distance(8)), 6);
+    }
+
+    #[test]
+    fn should_report_invalid_positions() {
+        assert_eq!(
+            parse_input("16,1,x2").err(),
+            Some(InputError::InvalidPosition {
+                line: 1,
+                column: 6,
+                value: "x2".to_string()
+            })
+        );
+        assert_eq!(parse_input("\n").err(), Some(InputError::Empty));
     }
 
     #[test]
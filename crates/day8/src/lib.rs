@@ -1,29 +1,64 @@
 use std::collections::HashSet;
-use std::ops::Deref;
-
-use itertools::Itertools;
-
-use crate::numbers::PERMUTATIONS;
-
-fn solve_pt_1() -> usize {
-    let input = include_str!("input");
-    let number_of_1478: usize = input
-        .trim()
-        .lines()
-        .map(|line| {
-            line.split('|')
-                .nth(1)
-                .expect("input should have a |")
-                .split_whitespace()
+
+use aoc::{Answer, Solution};
+use thiserror::Error;
+
+use crate::numbers::DIGITS;
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum InputError {
+    #[error("line {line}: expected the patterns and the output separated by '|'")]
+    MissingSeparator { line: usize },
+    #[error("line {line}, column {column}: '{char}' is not a segment between 'a' and 'g'")]
+    InvalidSegment {
+        line: usize,
+        column: usize,
+        char: char,
+    },
+    #[error("line {line}: {source}")]
+    Undecodable { line: usize, source: DecodeError },
+}
+
+/// Why the wiring of an entry could not be deduced from its patterns
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum DecodeError {
+    #[error("the patterns fit more than one wiring")]
+    Ambiguous,
+    #[error("no wiring turns all patterns into digits")]
+    Contradictory,
+    #[error("the output '{pattern}' is not a digit")]
+    UnknownOutput { pattern: String },
+}
+
+/// One line of notes, the ten unique patterns and the four digits of the output
+pub struct Entry {
+    line: usize,
+    patterns: Vec<HashSet<Segment>>,
+    output: Vec<HashSet<Segment>>,
+}
+
+fn count_1478(entries: &[Entry]) -> usize {
+    entries
+        .iter()
+        .map(|entry| {
+            entry
+                .output
+                .iter()
                 .filter(|segment| matches!(segment.len(), 2 | 3 | 4 | 7))
                 .count()
         })
-        .sum();
+        .sum()
+}
 
-    number_of_1478
+pub fn solve_pt_1() -> usize {
+    solve_pt_1_with_input(Day8::INPUT).expect("shipped input is valid")
 }
 
-#[derive(Eq, PartialEq, Hash, Copy, Clone)]
+pub fn solve_pt_1_with_input(input: &str) -> Result<usize, InputError> {
+    Ok(count_1478(&parse_entries(input)?))
+}
+
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone)]
 enum Segment {
     A,
     B,
@@ -35,22 +70,12 @@ enum Segment {
 }
 
 impl Segment {
-    fn get_permuted(&self, permutation: &[Segment]) -> Segment {
-        use Segment::*;
-        let (pos, _) = permutation
-            .iter()
-            .find_position(|&s| s == self)
-            .expect("could not de-permute");
-        match pos {
-            0 => A,
-            1 => B,
-            2 => C,
-            3 => D,
-            4 => E,
-            5 => F,
-            6 => G,
-            _ => panic!("no permutation found"),
-        }
+    fn bit(&self) -> u8 {
+        1 << *self as u8
+    }
+
+    fn to_char(self) -> char {
+        (b'a' + self as u8) as char
     }
 }
 
@@ -73,11 +98,18 @@ impl TryFrom<char> for Segment {
     }
 }
 
-fn to_segment_set(input: &str) -> HashSet<Segment> {
+fn to_segment_set(line_nr: usize, line: &str, input: &str) -> Result<HashSet<Segment>, InputError> {
+    let start = aoc::column(line, input);
     input
-        .trim()
         .chars()
-        .map(|c| Segment::try_from(c).expect("bad input"))
+        .enumerate()
+        .map(|(index, c)| {
+            Segment::try_from(c).map_err(|_| InputError::InvalidSegment {
+                line: line_nr,
+                column: start + index,
+                char: c,
+            })
+        })
         .collect()
 }
 
@@ -103,106 +135,279 @@ mod numbers {
     //!  gggg    gggg            gggg    gggg
     //! ```
 
-    use std::array::IntoIter;
-    use std::collections::HashSet;
+    /// The segments of a digit as a bit mask, with `a` as the lowest bit
+    const fn segments(letters: &[u8]) -> u8 {
+        let mut mask = 0;
+        let mut index = 0;
+        while index < letters.len() {
+            mask |= 1 << (letters[index] - b'a');
+            index += 1;
+        }
+        mask
+    }
 
-    use itertools::Itertools;
+    /// The segments of every digit, indexed by the digit
+    pub(crate) const DIGITS: [u8; 10] = [
+        segments(b"abcefg"),
+        segments(b"cf"),
+        segments(b"acdeg"),
+        segments(b"acdfg"),
+        segments(b"bcdf"),
+        segments(b"abdfg"),
+        segments(b"abdefg"),
+        segments(b"acf"),
+        segments(b"abcdefg"),
+        segments(b"abcdfg"),
+    ];
+}
 
-    use crate::Segment;
+/// The number of wires and segments of a display
+const SEGMENTS: usize = 7;
 
-    use super::Segment::*;
+fn mask(pattern: &HashSet<Segment>) -> u8 {
+    pattern.iter().fold(0, |mask, segment| mask | segment.bit())
+}
+
+/// How many of the masks have the given bit set
+fn occurrences(masks: &[u8], bit: usize) -> usize {
+    masks.iter().filter(|&&mask| mask & 1 << bit != 0).count()
+}
 
-    lazy_static::lazy_static! {
-        pub(crate) static ref ZERO: HashSet<Segment> = HashSet::<_>::from_iter(IntoIter::new([A, B, C, E, F, G]));
-        pub(crate) static ref ONE: HashSet<Segment> = HashSet::<_>::from_iter(IntoIter::new([C, F]));
-        pub(crate) static ref TWO: HashSet<Segment> = HashSet::<_>::from_iter(IntoIter::new([A, C, D, E, G]));
-        pub(crate) static ref THREE: HashSet<Segment> = HashSet::<_>::from_iter(IntoIter::new([A, C, D, F, G]));
-        pub(crate) static ref FOUR: HashSet<Segment> = HashSet::<_>::from_iter(IntoIter::new([B, C, D, F]));
-        pub(crate) static ref FIVE: HashSet<Segment> = HashSet::<_>::from_iter(IntoIter::new([A, B, D, F, G]));
-        pub(crate) static ref SIX: HashSet<Segment> = HashSet::<_>::from_iter(IntoIter::new([A, B, D, E, F, G]));
-        pub(crate) static ref SEVEN: HashSet<Segment> = HashSet::<_>::from_iter(IntoIter::new([A, C, F]));
-        pub(crate) static ref EIGHT: HashSet<Segment> = HashSet::<_>::from_iter(IntoIter::new([A, B, C, D, E, F, G]));
-        pub(crate) static ref NINE: HashSet<Segment> = HashSet::<_>::from_iter(IntoIter::new([A, B, C, D, F, G]));
-        pub(crate) static ref ALL: Vec<HashSet<Segment>> = vec![ZERO.clone(), ONE.clone(), TWO.clone(), THREE.clone(), FOUR.clone(), FIVE.clone(), SIX.clone(), SEVEN.clone(), EIGHT.clone(), NINE.clone()];
+/// For every wire, the mask of segments it may still be connected to
+type Candidates = [u8; SEGMENTS];
 
-        pub(crate) static ref PERMUTATIONS: Vec<Vec<Segment >> = vec![A, B, C, D, E, F, G].into_iter().permutations(7).collect();
+/// Narrows down the candidates with the patterns of every length.
+///
+/// A pattern can only light segments of the digits that are as long as it is.
+/// Once all digits of a length are known, a wire also has to appear in as many of their
+/// patterns as its segment appears in the digits, e.g. `c` and `f` in all patterns of
+/// length 2, 3 and 4, but only in two of the three patterns of length 6.
+fn restrict(candidates: &mut Candidates, patterns: &[u8]) -> Result<(), DecodeError> {
+    for length in 2..=SEGMENTS as u32 {
+        let digits: Vec<u8> = DIGITS
+            .iter()
+            .copied()
+            .filter(|digit| digit.count_ones() == length)
+            .collect();
+        let group: Vec<u8> = patterns
+            .iter()
+            .copied()
+            .filter(|pattern| pattern.count_ones() == length)
+            .collect();
+        if group.len() > digits.len() {
+            return Err(DecodeError::Contradictory);
+        }
+
+        let lit = digits.iter().fold(0, |mask, digit| mask | digit);
+        for pattern in &group {
+            for (wire, candidate) in candidates.iter_mut().enumerate() {
+                if pattern & 1 << wire != 0 {
+                    *candidate &= lit;
+                }
+            }
+        }
+
+        if !group.is_empty() && group.len() == digits.len() {
+            for (wire, candidate) in candidates.iter_mut().enumerate() {
+                let count = occurrences(&group, wire);
+                *candidate &= (0..SEGMENTS)
+                    .filter(|&segment| occurrences(&digits, segment) == count)
+                    .fold(0, |mask, segment| mask | 1 << segment);
+            }
+        }
     }
+    Ok(())
 }
 
-fn de_permutate(input: &HashSet<Segment>, permuation: &[Segment]) -> HashSet<Segment> {
-    input
+/// Removes candidates that are taken by other wires until nothing changes anymore
+fn propagate(candidates: &mut Candidates) -> Result<(), DecodeError> {
+    let mut changed = true;
+    while changed {
+        changed = false;
+        if candidates.contains(&0) {
+            return Err(DecodeError::Contradictory);
+        }
+
+        // a wire with a single candidate takes that segment away from all other wires
+        for wire in 0..SEGMENTS {
+            let taken = candidates[wire];
+            if taken.count_ones() != 1 {
+                continue;
+            }
+            for (other, candidate) in candidates.iter_mut().enumerate() {
+                if other != wire && *candidate & taken != 0 {
+                    *candidate &= !taken;
+                    changed = true;
+                }
+            }
+        }
+
+        // a segment that only one wire can light has to be lit by that wire
+        for segment in 0..SEGMENTS {
+            let bit = 1 << segment;
+            let mut wires = (0..SEGMENTS).filter(|&wire| candidates[wire] & bit != 0);
+            if let (Some(wire), None) = (wires.next(), wires.next()) {
+                if candidates[wire] != bit {
+                    candidates[wire] = bit;
+                    changed = true;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The digit that a pattern shows once its wires are connected as in the wiring
+fn translate(wiring: &Candidates, pattern: u8) -> Option<u32> {
+    let lit = (0..SEGMENTS)
+        .filter(|&wire| pattern & 1 << wire != 0)
+        .fold(0, |mask, wire| mask | wiring[wire]);
+    DIGITS
         .iter()
-        .map(|segment| segment.get_permuted(permuation))
-        .collect()
+        .position(|&digit| digit == lit)
+        .map(|digit| digit as u32)
 }
 
-fn is_a_number(input: &HashSet<Segment>) -> bool {
-    numbers::ALL.iter().any(|set| set == input)
-}
-
-fn translate_to_number(input: &HashSet<Segment>) -> u32 {
-    use numbers::*;
-    if input == ZERO.deref() {
-        0
-    } else if input == ONE.deref() {
-        1
-    } else if input == TWO.deref() {
-        2
-    } else if input == THREE.deref() {
-        3
-    } else if input == FOUR.deref() {
-        4
-    } else if input == FIVE.deref() {
-        5
-    } else if input == SIX.deref() {
-        6
-    } else if input == SEVEN.deref() {
-        7
-    } else if input == EIGHT.deref() {
-        8
-    } else if input == NINE.deref() {
-        9
-    } else {
-        panic!("could not translate")
-    }
-}
-
-fn solve_line(line: &str) -> u32 {
-    let mut split = line.split('|');
-    let input = split.next().expect("input should exist");
-    let reading = split.next().expect("reading should exist");
-
-    let input_segments: Vec<_> = parse_line_segment(input);
-    let reading_segments: Vec<_> = parse_line_segment(reading);
-
-    let correct_permutation = PERMUTATIONS
+/// Tries the candidates that are left over, collecting at most two wirings that fit
+fn search(
+    candidates: &Candidates,
+    patterns: &[u8],
+    wiring: &mut Candidates,
+    wire: usize,
+    found: &mut Vec<Candidates>,
+) {
+    if found.len() > 1 {
+        return;
+    }
+    if wire == SEGMENTS {
+        if patterns
+            .iter()
+            .all(|&pattern| translate(wiring, pattern).is_some())
+        {
+            found.push(*wiring);
+        }
+        return;
+    }
+
+    let used = wiring[..wire]
         .iter()
-        .find(|permutation| {
-            input_segments
-                .iter()
-                .all(|input_segment| is_a_number(&de_permutate(input_segment, permutation)))
-        })
-        .expect("no permutation found");
+        .fold(0, |mask, segment| mask | segment);
+    for segment in 0..SEGMENTS {
+        let bit = 1 << segment;
+        if candidates[wire] & bit != 0 && used & bit == 0 {
+            wiring[wire] = bit;
+            search(candidates, patterns, wiring, wire + 1, found);
+        }
+    }
+}
+
+/// Deduces which segment every wire is connected to.
+///
+/// The patterns usually pin down the wiring without any guessing, only what is left
+/// open after that is searched, to tell ambiguous and contradictory patterns apart.
+fn deduce_wiring(patterns: &[u8]) -> Result<Candidates, DecodeError> {
+    let mut patterns = patterns.to_vec();
+    patterns.sort_unstable();
+    patterns.dedup();
+
+    let mut candidates = [(1 << SEGMENTS) - 1; SEGMENTS];
+    restrict(&mut candidates, &patterns)?;
+    propagate(&mut candidates)?;
+
+    let mut found = Vec::new();
+    search(&candidates, &patterns, &mut [0; SEGMENTS], 0, &mut found);
+    match found[..] {
+        [wiring] => Ok(wiring),
+        [] => Err(DecodeError::Contradictory),
+        _ => Err(DecodeError::Ambiguous),
+    }
+}
+
+/// Deduces the wiring of an entry from its patterns and reads its output with it
+pub fn decode(entry: &Entry) -> Result<u32, DecodeError> {
+    let patterns: Vec<u8> = entry.patterns.iter().map(mask).collect();
+    let wiring = deduce_wiring(&patterns)?;
 
-    let reading_output = reading_segments
+    entry.output.iter().try_fold(0, |value, pattern| {
+        let digit = translate(&wiring, mask(pattern)).ok_or_else(|| {
+            let mut segments: Vec<_> = pattern.iter().copied().collect();
+            segments.sort_unstable();
+            DecodeError::UnknownOutput {
+                pattern: segments.into_iter().map(Segment::to_char).collect(),
+            }
+        })?;
+        Ok(value * 10 + digit)
+    })
+}
+
+fn decode_entries(entries: &[Entry]) -> Result<u32, InputError> {
+    entries
         .iter()
-        .map(|segment| de_permutate(segment, correct_permutation))
-        .map(|segment| translate_to_number(&segment))
-        .fold(0, |acc, elem| acc * 10 + elem);
-    reading_output
+        .map(|entry| {
+            decode(entry).map_err(|source| InputError::Undecodable {
+                line: entry.line,
+                source,
+            })
+        })
+        .sum()
+}
+
+fn parse_entry(line_nr: usize, line: &str) -> Result<Entry, InputError> {
+    let (input, reading) = line
+        .split_once('|')
+        .ok_or(InputError::MissingSeparator { line: line_nr })?;
+
+    Ok(Entry {
+        line: line_nr,
+        patterns: parse_line_segment(line_nr, line, input)?,
+        output: parse_line_segment(line_nr, line, reading)?,
+    })
+}
+
+fn parse_entries(input: &str) -> Result<Vec<Entry>, InputError> {
+    aoc::numbered_lines(input)
+        .map(|(line_nr, line)| parse_entry(line_nr, line))
+        .collect()
 }
 
-fn parse_line_segment(input: &str) -> Vec<HashSet<Segment>> {
+fn parse_line_segment(
+    line_nr: usize,
+    line: &str,
+    input: &str,
+) -> Result<Vec<HashSet<Segment>>, InputError> {
     input
-        .trim()
         .split_whitespace()
-        .map(to_segment_set)
+        .map(|segment| to_segment_set(line_nr, line, segment))
         .collect()
 }
 
-fn solve_pt_2() -> u32 {
-    let input = include_str!("input");
-    input.trim().lines().map(solve_line).sum()
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = Vec<Entry>;
+    type Error = InputError;
+
+    const INPUT: &'static str = include_str!("input");
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        parse_entries(input)
+    }
+
+    fn part1(entries: &Self::Input) -> Result<Answer, Self::Error> {
+        Ok(count_1478(entries).into())
+    }
+
+    fn part2(entries: &Self::Input) -> Result<Answer, Self::Error> {
+        Ok(decode_entries(entries)?.into())
+    }
+}
+
+pub fn solve_pt_2() -> u32 {
+    solve_pt_2_with_input(Day8::INPUT).expect("shipped input is valid")
+}
+
+pub fn solve_pt_2_with_input(input: &str) -> Result<u32, InputError> {
+    decode_entries(&parse_entries(input)?)
 }
 
 #[cfg(test)]
@@ -211,14 +416,59 @@ mod tests {
 
     #[test]
     fn should_init() {
-        assert_eq!(numbers::PERMUTATIONS.len(), 5040); // 7 factorial
+        let lengths: Vec<_> = DIGITS.iter().map(|digit| digit.count_ones()).collect();
+        assert_eq!(lengths, vec![6, 2, 5, 5, 4, 5, 6, 3, 7, 6]);
     }
 
     #[test]
     fn should_solve_a_line() {
         let input =
             "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
-        assert_eq!(solve_line(input), 5353);
+        assert_eq!(decode(&parse_entry(1, input).unwrap()), Ok(5353));
+
+        // the eight is not needed to tell the wires apart
+        let input = "cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+        assert_eq!(decode(&parse_entry(1, input).unwrap()), Ok(5353));
+    }
+
+    #[test]
+    fn should_report_undecodable_entries() {
+        let decode_line = |line| decode(&parse_entry(1, line).unwrap());
+
+        assert_eq!(decode_line("ab dab | ab"), Err(DecodeError::Ambiguous));
+        assert_eq!(decode_line("ab ac | ab"), Err(DecodeError::Contradictory));
+        assert_eq!(
+            decode_line("acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | ba cd"),
+            Err(DecodeError::UnknownOutput {
+                pattern: "cd".to_string()
+            })
+        );
+
+        let entries = Day8::parse("ab | ab\nab ac | ab").unwrap();
+        assert_eq!(Day8::part1(&entries), Ok(Answer::Unsigned(2)));
+        assert_eq!(
+            Day8::part2(&entries),
+            Err(InputError::Undecodable {
+                line: 1,
+                source: DecodeError::Ambiguous
+            })
+        );
+    }
+
+    #[test]
+    fn should_report_malformed_entries() {
+        assert_eq!(
+            parse_entries("ab cefabd\nacedgfb cdfbe").err(),
+            Some(InputError::MissingSeparator { line: 1 })
+        );
+        assert_eq!(
+            parse_entries("acedgfb cdfbe | cdfeb fcxdb").err(),
+            Some(InputError::InvalidSegment {
+                line: 1,
+                column: 25,
+                char: 'x'
+            })
+        );
     }
 
     #[test]
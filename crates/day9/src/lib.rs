@@ -1,9 +1,53 @@
-use ndarray::Array2;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::sync::Arc;
+
+use aoc::{Answer, Solution};
+use grid::{Neighbourhood, Position};
+use thiserror::Error;
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum InputError {
+    #[error("line {line}, column {column}: '{char}' is not a height")]
+    InvalidHeight {
+        line: usize,
+        column: usize,
+        char: char,
+    },
+    #[error("line {line}: expected {expected} heights but found {found}")]
+    UnevenRow {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[error("the height map is empty")]
+    Empty,
+}
+
+impl From<grid::ParseError> for InputError {
+    fn from(error: grid::ParseError) -> Self {
+        match error {
+            grid::ParseError::InvalidCell { line, column, char } => {
+                InputError::InvalidHeight { line, column, char }
+            }
+            grid::ParseError::UnevenRow {
+                line,
+                expected,
+                found,
+            } => InputError::UnevenRow {
+                line,
+                expected,
+                found,
+            },
+            grid::ParseError::Empty => InputError::Empty,
+        }
+    }
+}
 
 #[derive(Copy, Clone)]
 struct MapLocation {
     height: u32,
-    basin: Option<(usize, usize)>,
+    basin: Option<Position>,
 }
 
 impl MapLocation {
@@ -15,104 +59,403 @@ impl MapLocation {
     }
 }
 
+/// How the low points of a height map are found
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum LowPoints {
+    /// Only locations whose neighbours are all higher, as in the puzzle
+    #[default]
+    Strict,
+    /// Connected locations of equal height whose other neighbours are all higher form one
+    /// low region, with its first location row by row as its low point
+    Plateaus,
+}
+
+/// How the terrain of a height map is shaped
 #[derive(Clone)]
-struct Grid(Array2<MapLocation>);
+pub struct Terrain {
+    /// Which locations are adjacent to each other
+    pub neighbourhood: Neighbourhood,
+    pub low_points: LowPoints,
+    /// Whether a location of that height is a wall, walls belong to no basin
+    pub wall: Arc<dyn Fn(u32) -> bool + Send + Sync>,
+}
+
+impl Terrain {
+    pub fn with_neighbourhood(self, neighbourhood: Neighbourhood) -> Self {
+        Terrain {
+            neighbourhood,
+            ..self
+        }
+    }
+
+    pub fn with_low_points(self, low_points: LowPoints) -> Self {
+        Terrain { low_points, ..self }
+    }
+
+    pub fn with_wall(self, wall: impl Fn(u32) -> bool + Send + Sync + 'static) -> Self {
+        Terrain {
+            wall: Arc::new(wall),
+            ..self
+        }
+    }
+
+    fn is_wall(&self, location: &MapLocation) -> bool {
+        (self.wall)(location.height)
+    }
+}
+
+impl Default for Terrain {
+    /// The terrain of the puzzle, with four neighbours and walls of height 9
+    fn default() -> Self {
+        Terrain {
+            neighbourhood: Neighbourhood::Four,
+            low_points: LowPoints::Strict,
+            wall: Arc::new(|height| height == 9),
+        }
+    }
+}
+
+impl fmt::Debug for Terrain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Terrain")
+            .field("neighbourhood", &self.neighbourhood)
+            .field("low_points", &self.low_points)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A basin and all locations that flow into it
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Basin {
+    pub low_point: Position,
+    /// The locations of the basin, starting with the low point and ordered by their distance
+    /// to it
+    pub members: Vec<Position>,
+}
+
+impl Basin {
+    pub fn size(&self) -> usize {
+        self.members.len()
+    }
+}
+
+/// The lowest place at which water spills between two basins
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct SpillPoint {
+    /// The indices of the basins in [`Grid::basins`], the lower index first
+    pub basins: (usize, usize),
+    pub height: u32,
+    pub position: Position,
+}
+
+/// Two lakes that join as the water rises
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Flood {
+    /// The water level at which the lakes join
+    pub level: u32,
+    /// The location at which they join
+    pub position: Position,
+    /// The basins of both lakes, by their indices in [`Grid::basins`]
+    pub lakes: (Vec<usize>, Vec<usize>),
+}
+
+#[derive(Clone)]
+pub struct Grid {
+    locations: grid::Grid<MapLocation>,
+    terrain: Terrain,
+}
 
 impl Grid {
-    fn new(input: &str) -> Self {
-        let lines: Vec<&str> = input.trim().lines().collect();
-        let dim = (lines[0].len(), lines.len());
+    fn new(input: &str) -> Result<Self, InputError> {
+        Grid::with_terrain(input, Terrain::default())
+    }
+
+    /// Parses a height map whose low points are found as given
+    pub fn with_low_points(input: &str, low_points: LowPoints) -> Result<Self, InputError> {
+        Grid::with_terrain(input, Terrain::default().with_low_points(low_points))
+    }
+
+    /// Parses a height map of the given terrain
+    pub fn with_terrain(input: &str, terrain: Terrain) -> Result<Self, InputError> {
+        let heights = grid::Grid::parse(input, |char| char.to_digit(10))?;
 
-        let mut grid = Grid(Array2::from_shape_fn(dim, |(x, y)| {
-            let x1: &str = lines[y];
-            let char = x1.chars().nth(x).expect("should be in range");
-            MapLocation::new(char.to_digit(10).expect("input should be parsable chars"))
-        }));
-        grid.find_basins();
-        grid
+        let mut grid = Grid {
+            locations: heights.map(|&height| MapLocation::new(height)),
+            terrain,
+        };
+        match grid.terrain.low_points {
+            LowPoints::Strict => grid.find_basins(),
+            LowPoints::Plateaus => grid.find_plateau_basins(),
+        }
+        Ok(grid)
     }
 
     fn find_basins(&mut self) {
         let backup = self.clone();
 
-        self.0.indexed_iter_mut().for_each(|((x, y), value)| {
-            if backup
-                .get_neighbours(x, y)
-                .iter()
-                .all(|&neighbour| neighbour.height > value.height)
-            {
-                *value = MapLocation {
-                    height: value.height,
-                    basin: Some((x, y)),
+        self.locations
+            .indexed_iter_mut()
+            .for_each(|((x, y), value)| {
+                if !backup.terrain.is_wall(value)
+                    && backup
+                        .get_neighbours(x, y)
+                        .iter()
+                        .all(|&neighbour| neighbour.height > value.height)
+                {
+                    *value = MapLocation {
+                        height: value.height,
+                        basin: Some((x, y)),
+                    }
                 }
-            }
-        });
+            });
     }
 
-    fn promote_basins(&mut self) {
-        let backup = self.clone();
+    /// Finds the regions of equal height that only border higher locations, walls never form
+    /// a region
+    fn find_plateau_basins(&mut self) {
+        let mut visited = grid::Grid::<bool>::new(self.locations.width(), self.locations.height());
 
-        self.0.indexed_iter_mut().for_each(|((x, y), value)| {
-            if value.height == 9 {
-                return;
+        for start in self.locations.positions() {
+            if visited[start] {
+                continue;
             }
 
-            if let Some(basin) = backup
-                .get_neighbours(x, y)
-                .iter()
-                .flat_map(|&neighbour| neighbour.basin)
-                .next()
-            {
-                *value = MapLocation {
-                    height: value.height,
-                    basin: Some(basin),
+            let height = self.locations[start].height;
+            let mut lowest = !self.terrain.is_wall(&self.locations[start]);
+            let mut queue = VecDeque::from([start]);
+            visited[start] = true;
+            while let Some(position) = queue.pop_front() {
+                for neighbour in self
+                    .locations
+                    .neighbours(position, self.terrain.neighbourhood)
+                {
+                    let neighbour_height = self.locations[neighbour].height;
+                    if neighbour_height < height {
+                        lowest = false;
+                    } else if neighbour_height == height && !visited[neighbour] {
+                        visited[neighbour] = true;
+                        queue.push_back(neighbour);
+                    }
                 }
             }
-        });
+
+            if lowest {
+                self.locations[start].basin = Some(start);
+            }
+        }
     }
 
-    fn full_promote_basins(&mut self) {
-        let (x_max, y_max) = self.0.dim();
+    /// The basins of all low points, ordered row by row by their low point.
+    ///
+    /// The basins are flooded from all low points at once, so in the rare case that basins are
+    /// not separated by walls, each location goes to the closest low point.
+    pub fn basins(&self) -> Vec<Basin> {
+        self.label_basins().0
+    }
+
+    /// The basins, and for every location the index of the basin it belongs to
+    fn label_basins(&self) -> (Vec<Basin>, grid::Grid<Option<usize>>) {
+        let mut basins: Vec<Basin> = self
+            .locations
+            .positions()
+            .filter(|&position| self.locations[position].basin == Some(position))
+            .map(|low_point| Basin {
+                low_point,
+                members: Vec::new(),
+            })
+            .collect();
+
+        let mut labels: grid::Grid<Option<usize>> =
+            grid::Grid::new(self.locations.width(), self.locations.height());
+        let mut queue = VecDeque::new();
+        for (index, basin) in basins.iter().enumerate() {
+            labels[basin.low_point] = Some(index);
+            queue.push_back((basin.low_point, index));
+        }
 
-        for _ in 0..(x_max + y_max) {
-            self.promote_basins()
+        while let Some((position, index)) = queue.pop_front() {
+            basins[index].members.push(position);
+            for neighbour in self
+                .locations
+                .neighbours(position, self.terrain.neighbourhood)
+            {
+                if labels[neighbour].is_none() && !self.terrain.is_wall(&self.locations[neighbour])
+                {
+                    labels[neighbour] = Some(index);
+                    queue.push_back((neighbour, index));
+                }
+            }
         }
+        (basins, labels)
     }
 
-    fn collect_basins(&self) -> Vec<((usize, usize), usize)> {
-        use itertools::Itertools;
-        let basin_counts = self.0.iter().flat_map(|m| m.basin).counts();
+    /// The lowest spill point between every two basins that touch, ordered by the basins.
+    ///
+    /// The basins form a graph with these spill points as its edges. The water rises level by
+    /// level, and the locations of a level, walls included, take the basin of the water that
+    /// reaches them first. Two basins touch where locations of different basins are next to
+    /// each other, and the water spills over the higher of the two.
+    pub fn spill_points(&self) -> Vec<SpillPoint> {
+        let (_, basins) = self.label_basins();
+        let mut labels: grid::Grid<Option<usize>> =
+            grid::Grid::new(self.locations.width(), self.locations.height());
+        let mut flooded = grid::Grid::<bool>::new(self.locations.width(), self.locations.height());
 
-        basin_counts
-            .into_iter()
-            .sorted_by_key(|count| -(count.1 as isize))
-            .as_slice()[0..=2]
-            .to_vec()
-    }
+        let mut positions: Vec<Position> = self.locations.positions().collect();
+        positions.sort_by_key(|&(x, y)| (self.locations[(x, y)].height, y, x));
 
-    fn get_neighbours(&self, x: usize, y: usize) -> Vec<MapLocation> {
-        let mut neighbors: Vec<MapLocation> = Vec::new();
-        let (x_max, y_max) = self.0.dim();
+        let mut spill_points = BTreeMap::new();
+        for level in
+            positions.chunk_by(|&a, &b| self.locations[a].height == self.locations[b].height)
+        {
+            let height = self.locations[level[0]].height;
+            let on_level = |position: Position| {
+                !flooded[position] && self.locations[position].height == height
+            };
+
+            // the basins themselves first, then the locations next to lower water
+            let mut queue = VecDeque::new();
+            for &position in level {
+                labels[position] = basins[position];
+            }
+            queue.extend(
+                level
+                    .iter()
+                    .copied()
+                    .filter(|&position| labels[position].is_some()),
+            );
+            for &position in level {
+                if labels[position].is_none() {
+                    labels[position] = self
+                        .locations
+                        .neighbours(position, self.terrain.neighbourhood)
+                        .find_map(|neighbour| labels[neighbour].filter(|_| flooded[neighbour]));
+                    if labels[position].is_some() {
+                        queue.push_back(position);
+                    }
+                }
+            }
+            while let Some(position) = queue.pop_front() {
+                for neighbour in self
+                    .locations
+                    .neighbours(position, self.terrain.neighbourhood)
+                {
+                    if labels[neighbour].is_none() && on_level(neighbour) {
+                        labels[neighbour] = labels[position];
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
 
-        if x > 0 {
-            neighbors.push(self.0[[x - 1, y]])
+            for &position in level {
+                flooded[position] = true;
+                let Some(basin) = labels[position] else {
+                    continue;
+                };
+                for neighbour in self
+                    .locations
+                    .neighbours(position, self.terrain.neighbourhood)
+                {
+                    match labels[neighbour] {
+                        Some(other) if other != basin && flooded[neighbour] => {
+                            let basins = (basin.min(other), basin.max(other));
+                            spill_points.entry(basins).or_insert(SpillPoint {
+                                basins,
+                                height,
+                                position,
+                            });
+                        }
+                        _ => {}
+                    }
+                }
+            }
         }
-        if y > 0 {
-            neighbors.push(self.0[[x, y - 1]])
+        spill_points.into_values().collect()
+    }
+
+    /// Lets the water rise from the low points and reports every time two lakes join.
+    ///
+    /// The water covers the locations in the order of their height, row by row for equal
+    /// heights, walls included, and the lakes are joined with a union-find. A lake is only
+    /// reported once it holds at least one basin, so the floods join the basins like a tree.
+    pub fn rising_water(&self) -> Vec<Flood> {
+        let basins = self.basins();
+        let width = self.locations.width();
+        let index = |(x, y): Position| y * width + x;
+
+        let mut lakes: Vec<usize> = (0..self.locations.width() * self.locations.height()).collect();
+        let mut lake_basins: Vec<Vec<usize>> = vec![Vec::new(); lakes.len()];
+        for (basin, Basin { low_point, .. }) in basins.iter().enumerate() {
+            lake_basins[index(*low_point)].push(basin);
         }
 
-        if x < x_max - 1 {
-            neighbors.push(self.0[[x + 1, y]])
+        fn find(lakes: &mut [usize], mut location: usize) -> usize {
+            while lakes[location] != location {
+                lakes[location] = lakes[lakes[location]];
+                location = lakes[location];
+            }
+            location
         }
-        if y < y_max - 1 {
-            neighbors.push(self.0[[x, y + 1]])
+
+        let mut positions: Vec<Position> = self.locations.positions().collect();
+        positions.sort_by_key(|&(x, y)| (self.locations[(x, y)].height, y, x));
+
+        let mut flooded = grid::Grid::<bool>::new(width, self.locations.height());
+        let mut floods = Vec::new();
+        for position in positions {
+            flooded[position] = true;
+            for neighbour in self
+                .locations
+                .neighbours(position, self.terrain.neighbourhood)
+            {
+                if !flooded[neighbour] {
+                    continue;
+                }
+
+                let lake = find(&mut lakes, index(position));
+                let other = find(&mut lakes, index(neighbour));
+                if lake == other {
+                    continue;
+                }
+                if !lake_basins[lake].is_empty() && !lake_basins[other].is_empty() {
+                    floods.push(Flood {
+                        level: self.locations[position].height,
+                        position,
+                        lakes: (lake_basins[lake].clone(), lake_basins[other].clone()),
+                    });
+                }
+
+                lakes[other] = lake;
+                let mut joined = std::mem::take(&mut lake_basins[other]);
+                lake_basins[lake].append(&mut joined);
+                lake_basins[lake].sort_unstable();
+            }
         }
-        neighbors
+        floods
+    }
+
+    /// The low points and sizes of the `n` largest basins, fewer if there are not that many
+    pub fn collect_basins(&self, n: usize) -> Vec<(Position, usize)> {
+        let mut basins = self.basins();
+        basins.sort_by_key(|basin| std::cmp::Reverse(basin.size()));
+
+        basins
+            .iter()
+            .take(n)
+            .map(|basin| (basin.low_point, basin.size()))
+            .collect()
+    }
+
+    fn get_neighbours(&self, x: usize, y: usize) -> Vec<MapLocation> {
+        self.locations
+            .neighbours((x, y), self.terrain.neighbourhood)
+            .map(|position| self.locations[position])
+            .collect()
     }
 
     fn compute_risk_level(&self) -> u32 {
         let risk_level = self
-            .0
+            .locations
             .iter()
             .flat_map(|value| value.basin.map(|_| value.height + 1))
             .sum();
@@ -121,17 +464,45 @@ impl Grid {
     }
 }
 
-fn solve_pt_1() -> u32 {
-    let input = include_str!("input");
-    Grid::new(input).compute_risk_level()
+fn largest_basins_product(grid: &Grid) -> usize {
+    grid.collect_basins(3).into_iter().map(|b| b.1).product()
 }
 
-fn solve_pt_2() -> usize {
-    let input = include_str!("input");
-    let mut grid = Grid::new(input);
-    grid.full_promote_basins();
+pub struct Day9;
 
-    grid.collect_basins().into_iter().map(|b| b.1).product()
+impl Solution for Day9 {
+    type Input = Grid;
+    type Error = InputError;
+
+    const INPUT: &'static str = include_str!("input");
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        Grid::new(input)
+    }
+
+    fn part1(grid: &Self::Input) -> Result<Answer, Self::Error> {
+        Ok(grid.compute_risk_level().into())
+    }
+
+    fn part2(grid: &Self::Input) -> Result<Answer, Self::Error> {
+        Ok(largest_basins_product(grid).into())
+    }
+}
+
+pub fn solve_pt_1() -> u32 {
+    solve_pt_1_with_input(Day9::INPUT).expect("shipped input is valid")
+}
+
+pub fn solve_pt_1_with_input(input: &str) -> Result<u32, InputError> {
+    Ok(Grid::new(input)?.compute_risk_level())
+}
+
+pub fn solve_pt_2() -> usize {
+    solve_pt_2_with_input(Day9::INPUT).expect("shipped input is valid")
+}
+
+pub fn solve_pt_2_with_input(input: &str) -> Result<usize, InputError> {
+    Ok(largest_basins_product(&Grid::new(input)?))
 }
 
 #[cfg(test)]
@@ -146,11 +517,174 @@ mod tests {
 8767896789
 9899965678";
 
-        assert_eq!(Grid::new(input).compute_risk_level(), 15);
-        let mut grid = Grid::new(input);
-        grid.full_promote_basins();
-        let basins_sizes: Vec<_> = grid.collect_basins().iter().map(|b| b.1).collect();
+        assert_eq!(Grid::new(input).unwrap().compute_risk_level(), 15);
+        let grid = Grid::new(input).unwrap();
+        let basins_sizes: Vec<_> = grid.collect_basins(3).iter().map(|b| b.1).collect();
         assert_eq!(basins_sizes, vec![14, 9, 9]);
+
+        let basins = grid.basins();
+        let low_points: Vec<_> = basins.iter().map(|basin| basin.low_point).collect();
+        assert_eq!(low_points, vec![(1, 0), (9, 0), (2, 2), (6, 4)]);
+        assert_eq!(basins[0].members, vec![(1, 0), (0, 0), (0, 1)]);
+        let sizes: Vec<_> = basins.iter().map(Basin::size).collect();
+        assert_eq!(sizes, vec![3, 9, 14, 9]);
+    }
+
+    #[test]
+    fn should_find_plateaus() {
+        let valley = "9999\n9119\n9999";
+        let strict = Grid::new(valley).unwrap();
+        assert_eq!(strict.compute_risk_level(), 0);
+        assert_eq!(strict.collect_basins(3), vec![]);
+
+        let plateaus = Grid::with_low_points(valley, LowPoints::Plateaus).unwrap();
+        assert_eq!(plateaus.compute_risk_level(), 2);
+        assert_eq!(plateaus.collect_basins(3), vec![((1, 1), 2)]);
+        assert_eq!(largest_basins_product(&plateaus), 2);
+
+        // a plateau next to lower locations is a slope, not a low region
+        let slope = Grid::with_low_points("1221\n9999", LowPoints::Plateaus).unwrap();
+        assert_eq!(slope.compute_risk_level(), 4);
+        assert_eq!(slope.collect_basins(1), vec![((0, 0), 2)]);
+        assert!(Grid::with_low_points("99\n99", LowPoints::Plateaus)
+            .unwrap()
+            .basins()
+            .is_empty());
+    }
+
+    #[test]
+    fn should_respect_the_terrain() {
+        let diagonal = "9299\n9919\n9999";
+        let four = Grid::new(diagonal).unwrap();
+        assert_eq!(four.compute_risk_level(), 5);
+        assert_eq!(four.collect_basins(3), vec![((1, 0), 1), ((2, 1), 1)]);
+
+        let terrain = Terrain::default().with_neighbourhood(Neighbourhood::Eight);
+        let eight = Grid::with_terrain(diagonal, terrain).unwrap();
+        assert_eq!(eight.compute_risk_level(), 2);
+        assert_eq!(eight.collect_basins(3), vec![((2, 1), 2)]);
+
+        let low_ceiling = "1771\n7777";
+        let sizes = |grid: Grid| -> Vec<_> { grid.basins().iter().map(Basin::size).collect() };
+        assert_eq!(sizes(Grid::new(low_ceiling).unwrap()), vec![4, 4]);
+        let terrain = Terrain::default().with_wall(|height| height >= 7);
+        let walled = Grid::with_terrain(low_ceiling, terrain).unwrap();
+        assert_eq!(walled.compute_risk_level(), 4);
+        assert_eq!(sizes(walled), vec![1, 1]);
+    }
+
+    #[test]
+    fn should_find_spill_points_and_floods() {
+        let grid = Grid::new("15251\n99999").unwrap();
+        let members: Vec<_> = grid.basins().into_iter().map(|b| b.members).collect();
+        assert_eq!(
+            members,
+            vec![vec![(0, 0), (1, 0)], vec![(2, 0), (3, 0)], vec![(4, 0)]]
+        );
+
+        assert_eq!(
+            grid.spill_points(),
+            vec![
+                SpillPoint {
+                    basins: (0, 1),
+                    height: 5,
+                    position: (1, 0)
+                },
+                SpillPoint {
+                    basins: (1, 2),
+                    height: 5,
+                    position: (3, 0)
+                }
+            ]
+        );
+        assert_eq!(
+            grid.rising_water(),
+            vec![
+                Flood {
+                    level: 5,
+                    position: (1, 0),
+                    lakes: (vec![0], vec![1])
+                },
+                Flood {
+                    level: 5,
+                    position: (3, 0),
+                    lakes: (vec![0, 1], vec![2])
+                }
+            ]
+        );
+
+        // the wall at (3, 0) lies between basins 1 and 2, although basin 0 joins their lake first
+        let shallow = Grid::new("15191\n99999").unwrap();
+        assert_eq!(
+            shallow.spill_points(),
+            vec![
+                SpillPoint {
+                    basins: (0, 1),
+                    height: 5,
+                    position: (1, 0)
+                },
+                SpillPoint {
+                    basins: (1, 2),
+                    height: 9,
+                    position: (3, 0)
+                }
+            ]
+        );
+        assert_eq!(
+            shallow.rising_water()[1],
+            Flood {
+                level: 9,
+                position: (3, 0),
+                lakes: (vec![0, 1], vec![2])
+            }
+        );
+
+        let walled = Grid::new("1913\n9999").unwrap();
+        assert_eq!(
+            walled.spill_points(),
+            vec![SpillPoint {
+                basins: (0, 1),
+                height: 9,
+                position: (1, 0)
+            }]
+        );
+        let floods = walled.rising_water();
+        assert_eq!(floods.len(), 1);
+        assert_eq!((floods[0].level, floods[0].position), (9, (1, 0)));
+
+        let thick = Grid::new("1991\n9999").unwrap();
+        assert_eq!(
+            thick.spill_points(),
+            vec![SpillPoint {
+                basins: (0, 1),
+                height: 9,
+                position: (2, 0)
+            }]
+        );
+        let floods = thick.rising_water();
+        assert_eq!(floods.len(), 1);
+        assert_eq!((floods[0].level, floods[0].position), (9, (2, 0)));
+    }
+
+    #[test]
+    fn should_report_malformed_height_maps() {
+        assert_eq!(
+            Grid::new("2199\n39a7").err(),
+            Some(InputError::InvalidHeight {
+                line: 2,
+                column: 3,
+                char: 'a'
+            })
+        );
+        assert_eq!(
+            Grid::new("2199\n398").err(),
+            Some(InputError::UnevenRow {
+                line: 2,
+                expected: 4,
+                found: 3
+            })
+        );
+        assert_eq!(Grid::new("").err(), Some(InputError::Empty));
     }
 
     #[test]
@@ -0,0 +1,281 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use ndarray::Array2;
+use thiserror::Error;
+
+/// A position in a grid, `x` is the column and `y` the row
+pub type Position = (usize, usize);
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum ParseError {
+    #[error("line {line}, column {column}: '{char}' is not a valid cell")]
+    InvalidCell {
+        line: usize,
+        column: usize,
+        char: char,
+    },
+    #[error("line {line}: expected {expected} cells but found {found}")]
+    UnevenRow {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[error("the grid is empty")]
+    Empty,
+}
+
+/// Which cells count as adjacent to a cell
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Neighbourhood {
+    /// The cells left, above, right and below
+    Four,
+    /// The four cells of [`Neighbourhood::Four`] and the four diagonal ones
+    Eight,
+    /// The six cells around a hexagon, with every odd row shifted half a cell to the right
+    HexagonalOffset,
+}
+
+impl Neighbourhood {
+    /// The offsets of the neighbours of a cell in row `y`
+    fn offsets(&self, y: usize) -> &'static [(isize, isize)] {
+        match self {
+            Neighbourhood::Four => &[(-1, 0), (0, -1), (1, 0), (0, 1)],
+            Neighbourhood::Eight => &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+            Neighbourhood::HexagonalOffset if y.is_multiple_of(2) => {
+                &[(-1, -1), (0, -1), (-1, 0), (1, 0), (-1, 1), (0, 1)]
+            }
+            Neighbourhood::HexagonalOffset => &[(0, -1), (1, -1), (-1, 0), (1, 0), (0, 1), (1, 1)],
+        }
+    }
+}
+
+/// A rectangular grid of cells, indexed by `(x, y)` positions
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Grid<T>(Array2<T>);
+
+impl<T> Grid<T> {
+    /// Creates a grid of the given size, filled with the default value
+    pub fn new(width: usize, height: usize) -> Self
+    where
+        T: Default,
+    {
+        Grid(Array2::default((width, height)))
+    }
+
+    pub fn from_fn(width: usize, height: usize, f: impl FnMut(Position) -> T) -> Self {
+        Grid(Array2::from_shape_fn((width, height), f))
+    }
+
+    /// Parses a grid with one character per cell and one row per line.
+    ///
+    /// Empty lines are skipped, all other lines have to be equally long.
+    pub fn parse(input: &str, parse_cell: impl Fn(char) -> Option<T>) -> Result<Self, ParseError> {
+        let rows = aoc::numbered_lines(input)
+            .map(|(line_nr, line)| {
+                let start = aoc::column(line, line.trim_start());
+                line.trim()
+                    .chars()
+                    .enumerate()
+                    .map(|(index, char)| {
+                        parse_cell(char).ok_or(ParseError::InvalidCell {
+                            line: line_nr,
+                            column: start + index,
+                            char,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(|cells| (line_nr, cells))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let width = rows.first().ok_or(ParseError::Empty)?.1.len();
+        if let Some((line, cells)) = rows.iter().find(|(_, cells)| cells.len() != width) {
+            return Err(ParseError::UnevenRow {
+                line: *line,
+                expected: width,
+                found: cells.len(),
+            });
+        }
+
+        let height = rows.len();
+        let cells = rows.into_iter().flat_map(|(_, cells)| cells).collect();
+        let row_major = Array2::from_shape_vec((height, width), cells)
+            .expect("all rows are as wide as the first one");
+        Ok(Grid(row_major.reversed_axes()))
+    }
+
+    pub fn width(&self) -> usize {
+        self.0.dim().0
+    }
+
+    pub fn height(&self) -> usize {
+        self.0.dim().1
+    }
+
+    /// The width and height of the grid
+    pub fn dim(&self) -> (usize, usize) {
+        self.0.dim()
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.0.get(position)
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        self.0.get_mut(position)
+    }
+
+    /// The positions of all cells that are adjacent to `position` and inside the grid
+    pub fn neighbours(
+        &self,
+        (x, y): Position,
+        neighbourhood: Neighbourhood,
+    ) -> impl Iterator<Item = Position> {
+        let (width, height) = self.dim();
+        neighbourhood
+            .offsets(y)
+            .iter()
+            .filter_map(move |&(dx, dy)| {
+                let x = x.checked_add_signed(dx)?;
+                let y = y.checked_add_signed(dy)?;
+                (x < width && y < height).then_some((x, y))
+            })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.0.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.0.iter_mut()
+    }
+
+    pub fn indexed_iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.0.indexed_iter()
+    }
+
+    pub fn indexed_iter_mut(&mut self) -> impl Iterator<Item = (Position, &mut T)> {
+        self.0.indexed_iter_mut()
+    }
+
+    /// All positions of the grid, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let (width, height) = self.dim();
+        (0..height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid(self.0.map(f))
+    }
+
+    /// Renders the grid row by row, every row ends with a line break
+    pub fn render(&self, mut render_cell: impl FnMut(&T) -> String) -> String {
+        let mut rendered = String::new();
+        for row in self.0.columns() {
+            for cell in row {
+                rendered.push_str(&render_cell(cell));
+            }
+            rendered.push('\n');
+        }
+        rendered
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &Self::Output {
+        &self.0[position]
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut Self::Output {
+        &mut self.0[position]
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(|cell| cell.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> Result<Grid<u32>, ParseError> {
+        Grid::parse(input, |c| c.to_digit(10))
+    }
+
+    #[test]
+    fn should_parse_and_print() {
+        let grid = digits("123\n456\n").unwrap();
+
+        assert_eq!(grid.dim(), (3, 2));
+        assert_eq!(grid[(2, 0)], 3);
+        assert_eq!(grid[(0, 1)], 4);
+        assert_eq!(grid.to_string(), "123\n456\n");
+        assert_eq!(grid.render(|&c| (c * 2).to_string()), "246\n81012\n");
+    }
+
+    #[test]
+    fn should_report_malformed_grids() {
+        assert_eq!(
+            digits("123\n4a6"),
+            Err(ParseError::InvalidCell {
+                line: 2,
+                column: 2,
+                char: 'a'
+            })
+        );
+        assert_eq!(
+            digits("123\n\n45"),
+            Err(ParseError::UnevenRow {
+                line: 3,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(digits("\n"), Err(ParseError::Empty));
+    }
+
+    #[test]
+    fn should_stay_in_bounds() {
+        let grid: Grid<u32> = Grid::new(3, 2);
+
+        let corner: Vec<_> = grid.neighbours((0, 0), Neighbourhood::Four).collect();
+        assert_eq!(corner, vec![(1, 0), (0, 1)]);
+
+        let corner: Vec<_> = grid.neighbours((2, 1), Neighbourhood::Eight).collect();
+        assert_eq!(corner, vec![(1, 0), (1, 1), (2, 0)]);
+
+        assert_eq!(grid.neighbours((1, 0), Neighbourhood::Eight).count(), 5);
+
+        assert_eq!(
+            grid.positions().collect::<Vec<_>>(),
+            vec![(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)]
+        );
+
+        let grid: Grid<u32> = Grid::new(3, 3);
+        let even: Vec<_> = grid
+            .neighbours((1, 0), Neighbourhood::HexagonalOffset)
+            .collect();
+        assert_eq!(even, vec![(0, 0), (2, 0), (0, 1), (1, 1)]);
+        let odd: Vec<_> = grid
+            .neighbours((1, 1), Neighbourhood::HexagonalOffset)
+            .collect();
+        assert_eq!(odd, vec![(1, 0), (2, 0), (0, 1), (2, 1), (1, 2), (2, 2)]);
+    }
+}
@@ -0,0 +1,35 @@
+use aoc::Solution;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/// Benchmarks parsing and both parts of a day separately, each part on a parsed input
+fn bench_day<S: Solution>(c: &mut Criterion, day: u8) {
+    let mut group = c.benchmark_group(format!("day {}", day));
+    group.sample_size(10);
+
+    group.bench_function("parse", |b| b.iter(|| S::parse(black_box(S::INPUT))));
+
+    let input = S::parse(S::INPUT)
+        .map_err(|e| e.to_string())
+        .expect("shipped input is valid");
+    group.bench_function("part 1", |b| b.iter(|| S::part1(black_box(&input))));
+    group.bench_function("part 2", |b| b.iter(|| S::part2(black_box(&input))));
+
+    group.finish();
+}
+
+fn days(c: &mut Criterion) {
+    bench_day::<day1::Day1>(c, 1);
+    bench_day::<day2::Day2>(c, 2);
+    bench_day::<day3::Day3>(c, 3);
+    bench_day::<day4::Day4>(c, 4);
+    bench_day::<day5::Day5>(c, 5);
+    bench_day::<day6::Day6>(c, 6);
+    bench_day::<day7::Day7>(c, 7);
+    bench_day::<day8::Day8>(c, 8);
+    bench_day::<day9::Day9>(c, 9);
+    bench_day::<day10::Day10>(c, 10);
+    bench_day::<day11::Day11>(c, 11);
+}
+
+criterion_group!(benches, days);
+criterion_main!(benches);
@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::hint::black_box;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use aoc::Solution;
+
+use crate::days::DayRunner;
+
+/// The phases of a solution that are timed separately
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Phase {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 3] = [Phase::Parse, Phase::Part1, Phase::Part2];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Phase::Parse => "parse",
+            Phase::Part1 => "part1",
+            Phase::Part2 => "part2",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Phase::ALL.into_iter().find(|phase| phase.name() == name)
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The median durations of all phases of one day
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Timings(HashMap<Phase, Duration>);
+
+impl Timings {
+    pub fn get(&self, phase: Phase) -> Duration {
+        self.0[&phase]
+    }
+}
+
+/// Times every phase of a day on its shipped input
+pub struct Measure {
+    pub iterations: u32,
+}
+
+impl DayRunner for Measure {
+    type Output = Result<Timings, String>;
+
+    fn run<S: Solution>(self) -> Self::Output {
+        let mut samples: HashMap<Phase, Vec<Duration>> = HashMap::new();
+        let mut record = |phase: Phase, start: Instant| {
+            samples.entry(phase).or_default().push(start.elapsed());
+        };
+
+        for _ in 0..self.iterations.max(1) {
+            let start = Instant::now();
+            let input =
+                S::parse(black_box(S::INPUT)).map_err(|e| format!("shipped input: {}", e))?;
+            record(Phase::Parse, start);
+
+            let start = Instant::now();
+            black_box(S::part1(black_box(&input))).map_err(|e| format!("part 1: {}", e))?;
+            record(Phase::Part1, start);
+
+            let start = Instant::now();
+            black_box(S::part2(black_box(&input))).map_err(|e| format!("part 2: {}", e))?;
+            record(Phase::Part2, start);
+        }
+
+        Ok(Timings(
+            samples
+                .into_iter()
+                .map(|(phase, mut durations)| {
+                    durations.sort_unstable();
+                    (phase, durations[durations.len() / 2])
+                })
+                .collect(),
+        ))
+    }
+}
+
+/// Timings of earlier runs, keyed by day
+pub type Results = HashMap<u8, Timings>;
+
+/// Reads the results saved by an earlier run, a missing file has no results
+///
+/// Every day in the file needs a timing for each phase.
+pub fn load_results(path: &Path) -> Result<Results, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Results::new()),
+        Err(e) => return Err(format!("{}: {}", path.display(), e)),
+    };
+
+    let mut results = Results::new();
+    for (line_nr, line) in aoc::numbered_lines(&content).skip(1) {
+        let malformed = || format!("{}: line {} is malformed", path.display(), line_nr);
+        let mut fields = line.trim().split(',');
+        let (day, phase, nanos) = match (fields.next(), fields.next(), fields.next()) {
+            (Some(day), Some(phase), Some(nanos)) => (day, phase, nanos),
+            _ => return Err(malformed()),
+        };
+        let day = day.parse::<u8>().map_err(|_| malformed())?;
+        let phase = Phase::from_name(phase).ok_or_else(malformed)?;
+        let nanos = nanos.parse::<u64>().map_err(|_| malformed())?;
+
+        results
+            .entry(day)
+            .or_insert_with(|| Timings(HashMap::new()))
+            .0
+            .insert(phase, Duration::from_nanos(nanos));
+    }
+
+    for (day, timings) in &results {
+        if let Some(phase) = Phase::ALL
+            .into_iter()
+            .find(|phase| !timings.0.contains_key(phase))
+        {
+            return Err(format!(
+                "{}: day {} has no {} timing",
+                path.display(),
+                day,
+                phase
+            ));
+        }
+    }
+    Ok(results)
+}
+
+/// Saves the results as csv with one line per day and phase
+pub fn save_results(path: &Path, results: &Results) -> Result<(), String> {
+    let mut days: Vec<_> = results.keys().copied().collect();
+    days.sort_unstable();
+
+    let mut csv = String::from("day,phase,nanos\n");
+    for day in days {
+        for phase in Phase::ALL {
+            let nanos = results[&day].get(phase).as_nanos();
+            csv.push_str(&format!("{},{},{}\n", day, phase, nanos));
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+    }
+    fs::write(path, csv).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Formats a duration with a unit that keeps it readable
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.1}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.1}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2}s", nanos as f64 / 1e9)
+    }
+}
+
+/// Formats a timing, followed by its change relative to the baseline if there is one
+pub fn format_change(current: Duration, baseline: Option<Duration>) -> String {
+    let current_text = format_duration(current);
+    match baseline {
+        Some(baseline) if !baseline.is_zero() => {
+            let change = (current.as_secs_f64() / baseline.as_secs_f64() - 1.0) * 100.0;
+            format!("{} ({:+.1}%)", current_text, change)
+        }
+        _ => current_text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_save_and_load_results() {
+        let path = std::env::temp_dir().join(format!("tui-bench-{}.csv", std::process::id()));
+        let timings = Timings(
+            Phase::ALL
+                .into_iter()
+                .zip([1_500, 2_000_000, 3])
+                .map(|(phase, nanos)| (phase, Duration::from_nanos(nanos)))
+                .collect(),
+        );
+        let results = Results::from([(7, timings)]);
+
+        save_results(&path, &results).unwrap();
+        let loaded = load_results(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, results);
+        assert_eq!(load_results(&path).unwrap(), Results::new());
+
+        fs::write(&path, "day,phase,nanos\n1,parse,100\n").unwrap();
+        let partial = load_results(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(partial.unwrap_err().ends_with("day 1 has no part1 timing"));
+    }
+
+    #[test]
+    fn should_format_changes() {
+        let baseline = Duration::from_micros(200);
+        assert_eq!(
+            format_change(Duration::from_micros(250), Some(baseline)),
+            "250.0µs (+25.0%)"
+        );
+        assert_eq!(format_change(Duration::from_millis(3), None), "3.0ms");
+        assert_eq!(format_duration(Duration::from_nanos(12)), "12ns");
+    }
+}
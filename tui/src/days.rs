@@ -0,0 +1,41 @@
+use aoc::Solution;
+
+/// The number of days that have a solution in this workspace
+pub const DAYS: u8 = 11;
+
+/// Something that can be done with the solution of any day, e.g. solving or benchmarking it
+pub trait DayRunner {
+    type Output;
+
+    fn run<S: Solution>(self) -> Self::Output;
+}
+
+/// Hands the solution of the given day to the runner
+pub fn with_day<R: DayRunner>(day: u8, runner: R) -> R::Output {
+    match day {
+        1 => runner.run::<day1::Day1>(),
+        2 => runner.run::<day2::Day2>(),
+        3 => runner.run::<day3::Day3>(),
+        4 => runner.run::<day4::Day4>(),
+        5 => runner.run::<day5::Day5>(),
+        6 => runner.run::<day6::Day6>(),
+        7 => runner.run::<day7::Day7>(),
+        8 => runner.run::<day8::Day8>(),
+        9 => runner.run::<day9::Day9>(),
+        10 => runner.run::<day10::Day10>(),
+        11 => runner.run::<day11::Day11>(),
+        _ => unreachable!("day {} has no solution", day),
+    }
+}
+
+/// Checks that a day given on the command line has a solution
+pub fn check_day(day: u8) -> Result<u8, String> {
+    if (1..=DAYS).contains(&day) {
+        Ok(day)
+    } else {
+        Err(format!(
+            "day {} has no solution, pick one of 1..={}",
+            day, DAYS
+        ))
+    }
+}
@@ -1,3 +1,188 @@
-fn main() {
-    println!("Hello, world!");
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use aoc::{Answer, Solution};
+use clap::{Parser, Subcommand};
+
+use crate::days::{with_day, DayRunner, DAYS};
+
+mod bench;
+mod days;
+
+#[derive(Parser)]
+#[clap(about = "Runs my solutions for the Advent of Code 2021")]
+struct Cli {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve the puzzles and print their answers
+    Run {
+        /// The day to solve, all days if omitted
+        #[clap(long)]
+        day: Option<u8>,
+        /// The part to solve, both parts if omitted
+        #[clap(long)]
+        part: Option<u8>,
+        /// A file with the puzzle input, `-` reads it from stdin.
+        /// Uses the shipped input if omitted and needs a single day otherwise
+        #[clap(long)]
+        input: Option<PathBuf>,
+    },
+    /// Time parsing and both parts of every day on the shipped inputs.
+    /// Compares the timings with the previous run and saves them for the next one
+    Bench {
+        /// The day to time, all days if omitted
+        #[clap(long)]
+        day: Option<u8>,
+        /// How often every phase is timed, the median is reported
+        #[clap(long, default_value = "10")]
+        iterations: u32,
+        /// Where the timings are saved for comparison with later runs
+        #[clap(long, default_value = "target/bench.csv")]
+        results: PathBuf,
+    },
+}
+
+/// Where the puzzle input of a day is read from
+enum InputSource {
+    Shipped,
+    Stdin,
+    File(PathBuf),
+}
+
+impl InputSource {
+    fn new(input: Option<PathBuf>) -> Self {
+        match input {
+            None => InputSource::Shipped,
+            Some(path) if path.as_os_str() == "-" => InputSource::Stdin,
+            Some(path) => InputSource::File(path),
+        }
+    }
+
+    fn load<S: Solution>(&self) -> Result<S::Input, String> {
+        match self {
+            InputSource::Shipped => S::parse(S::INPUT).map_err(|e| format!("shipped input: {}", e)),
+            InputSource::Stdin => {
+                S::parse_reader(io::stdin().lock()).map_err(|e| format!("stdin: {}", e))
+            }
+            InputSource::File(path) => {
+                S::parse_file(path).map_err(|e| format!("{}: {}", path.display(), e))
+            }
+        }
+    }
+}
+
+/// Parses the input once and solves the requested parts on it
+struct Solve<'a> {
+    source: &'a InputSource,
+    parts: RangeInclusive<u8>,
+}
+
+impl DayRunner for Solve<'_> {
+    type Output = Result<Vec<(u8, Answer)>, String>;
+
+    fn run<S: Solution>(self) -> Self::Output {
+        let input = self.source.load::<S>()?;
+
+        self.parts
+            .map(|part| {
+                let answer = match part {
+                    1 => S::part1(&input),
+                    _ => S::part2(&input),
+                };
+                answer
+                    .map(|answer| (part, answer))
+                    .map_err(|e| format!("part {}: {}", part, e))
+            })
+            .collect()
+    }
+}
+
+fn run(day: Option<u8>, part: Option<u8>, input: Option<PathBuf>) -> Result<(), String> {
+    if let Some(day) = day {
+        days::check_day(day)?;
+    }
+    if let Some(part) = part {
+        if !(1..=2).contains(&part) {
+            return Err(format!("part {} does not exist, pick 1 or 2", part));
+        }
+    }
+
+    if input.is_some() && day.is_none() {
+        return Err("an input can only be given for a single --day".to_string());
+    }
+
+    let source = InputSource::new(input);
+    let days = day.map_or(1..=DAYS, |day| day..=day);
+    let parts = part.map_or(1..=2, |part| part..=part);
+
+    for day in days {
+        for (part, answer) in with_day(
+            day,
+            Solve {
+                source: &source,
+                parts: parts.clone(),
+            },
+        )? {
+            println!("Day {:>2}, part {}: {}", day, part, answer);
+        }
+    }
+    Ok(())
+}
+
+fn bench(day: Option<u8>, iterations: u32, results: &Path) -> Result<(), String> {
+    if let Some(day) = day {
+        days::check_day(day)?;
+    }
+
+    let mut saved = bench::load_results(results)?;
+    let days = day.map_or(1..=DAYS, |day| day..=day);
+
+    println!(
+        "{:>6} {:>22} {:>22} {:>22}",
+        "day", "parse", "part 1", "part 2"
+    );
+    for day in days {
+        let timings = with_day(day, bench::Measure { iterations })?;
+        let columns: Vec<_> = bench::Phase::ALL
+            .into_iter()
+            .map(|phase| {
+                let baseline = saved.get(&day).map(|previous| previous.get(phase));
+                bench::format_change(timings.get(phase), baseline)
+            })
+            .collect();
+        println!(
+            "{:>6} {:>22} {:>22} {:>22}",
+            day, columns[0], columns[1], columns[2]
+        );
+        saved.insert(day, timings);
+    }
+
+    bench::save_results(results, &saved)
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Command::Run { day, part, input } => run(day, part, input),
+        Command::Bench {
+            day,
+            iterations,
+            results,
+        } => bench(day, iterations, &results),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
 }